@@ -0,0 +1,190 @@
+use std::sync::{atomic::AtomicBool, Arc};
+use std::time::Duration;
+
+use crate::board::{Board, Coordinate};
+use crate::book::OpeningBook;
+use crate::difficulty::Difficulty;
+use crate::endgame::{self, Solution};
+use crate::piece::Piece;
+use crate::position::Position;
+use crate::search::{position_moves, CompoundMove, Search, SearchResult};
+use crate::transposition::DEFAULT_TABLE_SIZE_MB;
+
+/// How the AI came to its last compound move.
+#[derive(Debug, Clone)]
+pub enum Reasoning {
+    Book,
+    /// Any legal move, played instead of thinking.
+    Blunder,
+    Endgame(Solution),
+    Search(SearchResult),
+    /// Best move once the score of every move, scored to `depth`, was made
+    /// noisy.
+    Noisy {
+        depth: u32,
+        score: i32,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct AI {
+    level: usize,
+    /// Deepest search, in compound moves (place, then give).
+    pub depth: u32,
+    /// Thinking time per move. The search goes deeper until it runs out.
+    pub time_budget: Duration,
+    /// See `difficulty::Settings::noise`.
+    pub noise: i32,
+    /// Chance in percent of playing any legal move instead of thinking.
+    pub blunder_percent: u32,
+    /// Plays endgames perfectly with `endgame::solve` once few enough pieces
+    /// are left, whatever the time budget.
+    pub solve_endgames: bool,
+    /// Consulted before searching, while the game is still in the book.
+    pub book: Option<Arc<OpeningBook>>,
+    /// Piece the last placement search meant to hand over next, with the board
+    /// after that placement.
+    planned_give: Option<(Board, Piece)>,
+    last_reasoning: Option<Reasoning>,
+    search: Search,
+    rng: oorandom::Rand32,
+}
+
+impl AI {
+    pub fn init(level: usize) -> Self {
+        Self::with_seed(level, 4)
+    }
+
+    /// AI with the settings of the difficulty of `level`, whose random choices
+    /// follow `seed`. It searches on one thread, so the same seed plays the
+    /// same moves; see `set_threads`.
+    pub fn with_seed(level: usize, seed: u64) -> Self {
+        Self::with_table_size(level, seed, DEFAULT_TABLE_SIZE_MB)
+    }
+
+    /// Same as `with_seed`, keeping search results in a transposition table
+    /// of `table_size_mb` megabytes.
+    pub fn with_table_size(level: usize, seed: u64, table_size_mb: usize) -> Self {
+        let settings = Difficulty::from_level(level).settings();
+        let search = Search::with_seed(seed, table_size_mb);
+
+        Self {
+            level,
+            depth: settings.depth,
+            time_budget: settings.time_budget,
+            noise: settings.noise,
+            blunder_percent: settings.blunder_percent,
+            solve_endgames: settings.solve_endgames,
+            book: None,
+            planned_give: None,
+            last_reasoning: None,
+            search,
+            rng: oorandom::Rand32::new(seed),
+        }
+    }
+
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    /// Threads to search on, one by default. More threads search deeper in
+    /// the same time, but share bounds and table entries as they go, so the
+    /// moves they find depend on timing and no longer on the seed alone.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.search.set_threads(threads);
+    }
+
+    /// Makes the AI give up thinking once `stop` is set; see
+    /// `Search::set_stop_flag`.
+    pub fn set_stop_flag(&mut self, stop: Arc<AtomicBool>) {
+        self.search.set_stop_flag(stop);
+    }
+
+    /// How the last compound move was found, unless it was planned by an
+    /// earlier search.
+    pub fn last_reasoning(&self) -> Option<&Reasoning> {
+        self.last_reasoning.as_ref()
+    }
+
+    /// Chooses the piece to hand over to the other side.
+    pub fn select_piece(&mut self, position: &Position) -> Piece {
+        match self.planned_give.take() {
+            Some((board, piece))
+                if board == position.board && position.available_pieces.contains(&piece) =>
+            {
+                self.last_reasoning = None;
+                piece
+            }
+            _ => self
+                .search(position)
+                .give
+                .expect("a piece can be given while pieces are available"),
+        }
+    }
+
+    /// Chooses an empty cell of the board to place the piece in hand on.
+    pub fn select_place(&mut self, position: &Position) -> Coordinate {
+        let best = self.search(position);
+        let place = best.place.expect("a cell is free while a piece is in hand");
+
+        let mut board = position.board;
+        board.set(place, position.piece_in_hand());
+        self.planned_give = best.give.map(|piece| (board, piece));
+        place
+    }
+
+    fn search(&mut self, position: &Position) -> CompoundMove {
+        let roll = self.rng.rand_u32();
+        if let Some(mv) = self.book.as_ref().and_then(|book| book.choose(position, roll)) {
+            log::info!("book move {}", mv);
+            self.last_reasoning = Some(Reasoning::Book);
+            return mv;
+        }
+
+        if self.blunder_percent > 0 && self.rng.rand_range(0..100) < self.blunder_percent {
+            let moves = position_moves(position);
+            if !moves.is_empty() {
+                let mv = moves[self.rng.rand_range(0..moves.len() as u32) as usize];
+                log::info!("blunder {}", mv);
+                self.last_reasoning = Some(Reasoning::Blunder);
+                return mv;
+            }
+        }
+
+        if self.solve_endgames {
+            if let Some(solution) = endgame::solve_with(&mut self.search, position) {
+                log::info!(
+                    "endgame {:?} in {} nodes, best {}",
+                    solution.verdict,
+                    solution.nodes,
+                    solution.best
+                );
+                let best = solution.best;
+                self.last_reasoning = Some(Reasoning::Endgame(solution));
+                return best;
+            }
+        }
+
+        if self.noise > 0 {
+            let noise = self.noise;
+            let rng = &mut self.rng;
+            let mut noisy =
+                |score: i32| score + rng.rand_range(0..2 * noise as u32 + 1) as i32 - noise;
+            let (scores, depth) =
+                self.search
+                    .score_moves_for(position, self.time_budget, self.depth);
+            let (best, score) = scores
+                .into_iter()
+                .map(|(mv, score)| (mv, noisy(score)))
+                .max_by_key(|(_, score)| *score)
+                .expect("a position with moves left is searched");
+            self.last_reasoning = Some(Reasoning::Noisy { depth, score });
+            return best;
+        }
+
+        let result = self.search.run_for(position, self.time_budget, self.depth);
+        let best = result.best;
+        self.last_reasoning = Some(Reasoning::Search(result));
+        best
+    }
+}
@@ -0,0 +1,174 @@
+//! Advice for the side to move: where to place the piece in hand, which piece
+//! is the safest to hand over, and how the position looks. Also the threats on
+//! the board, and the pieces they make poisoned to hand over.
+
+use std::{fmt, time::Duration};
+
+use crate::{
+    board::{Board, Coordinate, Line},
+    endgame::Verdict,
+    evaluation::LOST_PLACEMENT_SCORE,
+    piece::{Attribute, Piece},
+    position::Position,
+    search::{is_decisive, position_moves, Search},
+    transposition::SMALL_TABLE_SIZE_MB,
+};
+
+pub const HINT_TIME_BUDGET: Duration = Duration::from_secs(1);
+
+// Deepest search for a hint; the time budget usually ends it first
+const HINT_MAX_DEPTH: u32 = 16;
+
+/// How the position looks for the side to move.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Assessment {
+    /// The side to move wins whatever the other side does.
+    Winning,
+    /// The other side wins whatever the side to move does.
+    Losing,
+    /// Neither side can force a win as far as the search sees, or the game is
+    /// a proven draw.
+    Unclear,
+}
+
+impl Assessment {
+    /// Assessment of a search score, for the side to move. Besides the
+    /// decisive scores, a static score of `LOST_PLACEMENT_SCORE` means that
+    /// every piece left to give completes a Quarto, which loses as well.
+    pub fn from_score(score: i32) -> Self {
+        if !is_decisive(score) && score.abs() < -LOST_PLACEMENT_SCORE {
+            Assessment::Unclear
+        } else if score > 0 {
+            Assessment::Winning
+        } else {
+            Assessment::Losing
+        }
+    }
+}
+
+impl From<Verdict> for Assessment {
+    fn from(verdict: Verdict) -> Self {
+        match verdict {
+            Verdict::Win { .. } => Assessment::Winning,
+            Verdict::Loss { .. } => Assessment::Losing,
+            Verdict::Draw => Assessment::Unclear,
+        }
+    }
+}
+
+impl fmt::Display for Assessment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Assessment::Winning => "winning",
+            Assessment::Losing => "losing",
+            Assessment::Unclear => "unclear",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Hint {
+    /// Best cell for the piece in hand, when there is one to place.
+    pub place: Option<Coordinate>,
+    /// Safest piece to hand over afterwards, unless the game is over by then.
+    pub give: Option<Piece>,
+    pub assessment: Assessment,
+    /// Whether the assessment is proven, rather than the view of a search
+    /// that stopped short of the end of the game.
+    pub exact: bool,
+}
+
+/// Best move of the side to move in `position`, thinking for up to `budget`
+/// on every core. Endgames are solved exactly when the budget allows. `None`
+/// when there is no move to play.
+pub fn hint(position: &Position, budget: Duration) -> Option<Hint> {
+    let mut search = Search::new(SMALL_TABLE_SIZE_MB);
+    search.set_threads(rayon::current_num_threads());
+    hint_with(&mut search, position, budget)
+}
+
+/// Same as `hint`, thinking with `search`. `None` as well when the stop flag
+/// of `search` was set before the hint was worked out.
+pub fn hint_with(search: &mut Search, position: &Position, budget: Duration) -> Option<Hint> {
+    if position_moves(position).is_empty() {
+        return None;
+    }
+
+    let result = search.run_for(position, budget, HINT_MAX_DEPTH);
+    if search.is_stopped() {
+        return None;
+    }
+
+    // Searched to the end of the game, the score is the outcome under perfect
+    // play, as `endgame::solve` would find it
+    let solved = result.depth > position.available_pieces.len() as u32;
+    Some(Hint {
+        place: result.best.place,
+        give: result.best.give,
+        assessment: Assessment::from_score(result.score),
+        exact: solved || is_decisive(result.score),
+    })
+}
+
+/// Line with three pieces sharing attribute values and a free fourth cell: a
+/// piece with any of those values placed there completes a Quarto.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Threat {
+    pub line: Line,
+    /// The free cell of the line.
+    pub cell: Coordinate,
+    /// Cells of the three pieces.
+    pub pieces: [Coordinate; 3],
+    /// Values shared by the three pieces, any of which completes the line.
+    pub attributes: Vec<Attribute>,
+}
+
+/// Every threat on `board`, in the order of `Line::all`.
+pub fn threats(board: &Board) -> Vec<Threat> {
+    Line::all()
+        .into_iter()
+        .filter_map(|line| {
+            let coords = line.coords();
+            let (free, taken): (Vec<_>, Vec<_>) =
+                coords.into_iter().partition(|coor| board.is_free(*coor));
+            if free.len() != 1 {
+                return None;
+            }
+
+            let pieces = taken
+                .iter()
+                .filter_map(|coor| board.get(*coor))
+                .collect::<Vec<_>>();
+            let shared = Piece::shared_attributes(&pieces);
+            if shared == 0 {
+                return None;
+            }
+
+            Some(Threat {
+                line,
+                cell: free[0],
+                pieces: [taken[0], taken[1], taken[2]],
+                attributes: pieces[0].attributes(shared),
+            })
+        })
+        .collect()
+}
+
+/// Whether handing `piece` over lets the other side complete a Quarto with it
+/// right away.
+pub fn is_poisoned(board: &Board, piece: Piece) -> bool {
+    board
+        .free_coords()
+        .any(|coor| board.is_winning_placement(coor, piece))
+}
+
+/// The pieces of `pieces` that are poisoned on `board`, e.g. the available
+/// pieces of a game while choosing one to give.
+pub fn poisoned_pieces(board: &Board, pieces: &[Piece]) -> Vec<Piece> {
+    pieces
+        .iter()
+        .copied()
+        .filter(|piece| is_poisoned(board, *piece))
+        .collect()
+}
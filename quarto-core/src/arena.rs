@@ -0,0 +1,227 @@
+//! Headless matches between engines, scored with win/draw/loss records and Elo
+//! ratings. Used by the `arena` binary to check that stronger settings really
+//! play better.
+
+use std::{fmt, time::Duration};
+
+use crate::{
+    ai::AI,
+    difficulty::Difficulty,
+    game::{Game, GameOutcome, Turn},
+    mcts::Mcts,
+    player::{Player, Players},
+};
+
+// Fixed depth searches are given this long, so that the depth ends them rather
+// than the clock and their games follow their seeds
+const MINIMAX_TIME_BUDGET: Duration = Duration::from_secs(3600);
+
+// Games are played side by side, each engine with a table of its own
+const ENGINE_TABLE_SIZE_MB: usize = 4;
+
+// Elo ratings are fitted until no rating moves by more than this
+const ELO_PRECISION: f64 = 0.01;
+const ELO_MAX_ITERATIONS: usize = 10_000;
+
+/// Engine taking part in a match, as written on the command line.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EngineSpec {
+    /// `AI::init` at this level, with the settings of its difficulty.
+    Level(usize),
+    /// Search to this depth, with no time limit nor endgame solver.
+    Minimax(u32),
+    /// Monte Carlo tree search with this many iterations per move.
+    Mcts(u32),
+}
+
+impl EngineSpec {
+    /// Reads `ai:<level>`, `minimax:<depth>` or `mcts:<iterations>`. The
+    /// level may be given by the name of its difficulty, e.g. `ai:hard`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (kind, value) = text.split_once(':')?;
+        match kind {
+            "ai" => {
+                Difficulty::parse(value).map(|difficulty| EngineSpec::Level(difficulty.level()))
+            }
+            "minimax" => value
+                .parse()
+                .ok()
+                .filter(|depth| *depth > 0)
+                .map(EngineSpec::Minimax),
+            "mcts" => value.parse().ok().filter(|n| *n > 0).map(EngineSpec::Mcts),
+            _ => None,
+        }
+    }
+
+    /// Engine whose choices follow `seed`. Levels think for `time_budget`
+    /// when one is given, instead of their own budget. Searches run on one
+    /// thread, so that games can be played side by side.
+    pub fn player(self, seed: u64, time_budget: Option<Duration>) -> Box<dyn Player + Send> {
+        match self {
+            EngineSpec::Level(level) => {
+                let mut ai = AI::with_table_size(level, seed, ENGINE_TABLE_SIZE_MB);
+                ai.set_threads(1);
+                if let Some(time_budget) = time_budget {
+                    ai.time_budget = time_budget;
+                }
+                Box::new(ai)
+            }
+            EngineSpec::Minimax(depth) => {
+                let mut ai =
+                    AI::with_table_size(Difficulty::Hard.level(), seed, ENGINE_TABLE_SIZE_MB);
+                ai.set_threads(1);
+                ai.depth = depth;
+                ai.time_budget = MINIMAX_TIME_BUDGET;
+                ai.noise = 0;
+                ai.blunder_percent = 0;
+                ai.solve_endgames = false;
+                Box::new(ai)
+            }
+            EngineSpec::Mcts(iterations) => Box::new(Mcts::new(iterations, seed)),
+        }
+    }
+}
+
+impl fmt::Display for EngineSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineSpec::Level(level) => write!(f, "ai:{}", level),
+            EngineSpec::Minimax(depth) => write!(f, "minimax:{}", depth),
+            EngineSpec::Mcts(iterations) => write!(f, "mcts:{}", iterations),
+        }
+    }
+}
+
+/// Games of one engine against another, from the first engine's side.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl Record {
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Points scored, a win counting one and a draw a half.
+    pub fn points(&self) -> f64 {
+        self.wins as f64 + self.draws as f64 / 2.0
+    }
+
+    /// Share of the points scored, from 0 to 1.
+    pub fn score(&self) -> f64 {
+        match self.games() {
+            0 => 0.5,
+            games => self.points() / games as f64,
+        }
+    }
+
+    /// The same games from the other engine's side.
+    pub fn reversed(&self) -> Self {
+        Self {
+            wins: self.losses,
+            draws: self.draws,
+            losses: self.wins,
+        }
+    }
+
+    /// Counts a game that ended in `outcome`, where the first engine played
+    /// `side`.
+    pub fn add(&mut self, outcome: &GameOutcome, side: Turn) {
+        match outcome {
+            GameOutcome::Won { by, .. } if *by == side => self.wins += 1,
+            GameOutcome::Won { .. } => self.losses += 1,
+            GameOutcome::Draw | GameOutcome::InProgress => self.draws += 1,
+        }
+    }
+}
+
+/// Record of the engine `first` against `second`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Pairing {
+    pub first: usize,
+    pub second: usize,
+    pub record: Record,
+}
+
+/// Plays one game, `first` starting. Each engine follows a seed of its own,
+/// both worked out from `seed`.
+pub fn play_game(
+    first: EngineSpec,
+    second: EngineSpec,
+    seed: u64,
+    time_budget: Option<Duration>,
+) -> GameOutcome {
+    let mut game = Game::init(1);
+    let mut players = Players::new(
+        first.player(seed.wrapping_mul(2), time_budget),
+        second.player(seed.wrapping_mul(2).wrapping_add(1), time_budget),
+    );
+
+    players.play(&mut game).expect("engines play legal moves");
+    game.outcome
+}
+
+/// Elo difference matching `score`, the share of points of one engine against
+/// another. `None` when one of them scored every point.
+pub fn elo_difference(score: f64) -> Option<f64> {
+    if score <= 0.0 || score >= 1.0 {
+        return None;
+    }
+    Some(-400.0 * (1.0 / score - 1.0).log10())
+}
+
+/// Expected share of points of an engine rated `difference` above its
+/// opponent.
+pub fn expected_score(difference: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-difference / 400.0))
+}
+
+/// Elo ratings of `engines` engines that best explain `pairings`, averaging
+/// zero. Each pairing also counts one drawn game, which keeps the ratings of
+/// engines that won or lost every game finite.
+pub fn elo_ratings(engines: usize, pairings: &[Pairing]) -> Vec<f64> {
+    let mut ratings = vec![0.0; engines];
+
+    for _ in 0..ELO_MAX_ITERATIONS {
+        let mut largest_change: f64 = 0.0;
+
+        for engine in 0..engines {
+            let mut points = 0.0;
+            let mut expected = 0.0;
+            let mut variance = 0.0;
+
+            for pairing in pairings {
+                let (record, opponent) = if pairing.first == engine {
+                    (pairing.record, pairing.second)
+                } else if pairing.second == engine {
+                    (pairing.record.reversed(), pairing.first)
+                } else {
+                    continue;
+                };
+
+                let games = record.games() as f64 + 1.0;
+                let p = expected_score(ratings[engine] - ratings[opponent]);
+                points += record.points() + 0.5;
+                expected += games * p;
+                variance += games * p * (1.0 - p);
+            }
+
+            if variance > 0.0 {
+                // Newton step on the log-likelihood of the results
+                let change = (points - expected) / variance * 400.0 / 10f64.ln();
+                ratings[engine] += change;
+                largest_change = largest_change.max(change.abs());
+            }
+        }
+
+        if largest_change < ELO_PRECISION {
+            break;
+        }
+    }
+
+    let mean = ratings.iter().sum::<f64>() / engines.max(1) as f64;
+    ratings.iter().map(|rating| rating - mean).collect()
+}
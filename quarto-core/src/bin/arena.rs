@@ -0,0 +1,181 @@
+//! Plays engines against each other and rates them.
+//!
+//! ```text
+//! cargo run --release -p quarto-core --bin arena -- --engines ai:easy,ai:medium,minimax:3,mcts:2000 --games 200
+//! ```
+//!
+//! Every engine plays `--games` games against every other one, each of the two
+//! starting every other game. Engines are written `ai:<level>`, `minimax:<depth>`
+//! or `mcts:<iterations>`, where the level is the name or the number of a
+//! difficulty. By default, every difficulty plays. The game with number `n` of
+//! a pairing is seeded with `--seed` plus `n`, so a run can be played again;
+//! levels thinking against the clock only repeat their games as far as their
+//! timing does. `--time` sets the thinking time of levels in milliseconds,
+//! instead of their own. The games are spread over every core, each engine
+//! searching on one thread.
+//!
+//! The win/draw/loss table reads from the side of the engine of each row. The
+//! Elo ratings are fitted to every game played and average zero.
+
+use std::{env, process, time::Duration};
+
+use quarto_core::{
+    arena::{self, EngineSpec, Pairing, Record},
+    cli::arg,
+    Difficulty, Turn,
+};
+use rayon::prelude::*;
+
+fn main() {
+    let args = env::args().collect::<Vec<_>>();
+    let all_levels = Difficulty::ALL
+        .iter()
+        .map(|difficulty| format!("ai:{}", difficulty.level()))
+        .collect::<Vec<_>>();
+    let engines = arg(&args, "--engines", all_levels.join(","))
+        .split(',')
+        .map(|engine| EngineSpec::parse(engine.trim()))
+        .collect::<Option<Vec<_>>>()
+        .unwrap_or_else(|| {
+            eprintln!(
+                "--engines expects engines separated by commas, each ai:<level>, minimax:<depth> or mcts:<iterations>"
+            );
+            process::exit(2);
+        });
+    let games = arg(&args, "--games", 100u64);
+    let seed = arg(&args, "--seed", 1u64);
+    let time_budget = if args.iter().any(|arg| arg == "--time") {
+        Some(Duration::from_millis(arg(&args, "--time", 0u64)))
+    } else {
+        None
+    };
+
+    if engines.len() < 2 {
+        eprintln!("--engines expects at least two engines");
+        process::exit(2);
+    }
+
+    let mut pairings = vec![];
+    for first in 0..engines.len() {
+        for second in first + 1..engines.len() {
+            pairings.push(Pairing {
+                first,
+                second,
+                record: Record::default(),
+            });
+        }
+    }
+    println!(
+        "{} engines, {} games per pairing, {} games, seed {}",
+        engines.len(),
+        games,
+        games * pairings.len() as u64,
+        seed
+    );
+
+    let jobs = (0..pairings.len())
+        .flat_map(|pairing| (0..games).map(move |game| (pairing, game)))
+        .collect::<Vec<_>>();
+    let outcomes = jobs
+        .par_iter()
+        .map(|&(pairing, game)| {
+            let Pairing { first, second, .. } = pairings[pairing];
+            let game_seed = seed.wrapping_add(game);
+            // The first engine of the pairing starts the even games
+            if game % 2 == 0 {
+                let outcome =
+                    arena::play_game(engines[first], engines[second], game_seed, time_budget);
+                (pairing, outcome, Turn::Player)
+            } else {
+                let outcome =
+                    arena::play_game(engines[second], engines[first], game_seed, time_budget);
+                (pairing, outcome, Turn::Opponent)
+            }
+        })
+        .collect::<Vec<_>>();
+    for (pairing, outcome, side) in outcomes {
+        pairings[pairing].record.add(&outcome, side);
+    }
+
+    let names = engines
+        .iter()
+        .map(|engine| engine.to_string())
+        .collect::<Vec<_>>();
+    let width = names
+        .iter()
+        .map(|name| name.len())
+        .max()
+        .unwrap_or(0)
+        .max(8);
+
+    println!();
+    print!("{:width$}", "", width = width);
+    for name in &names {
+        print!(" {:>width$}", name, width = width);
+    }
+    println!();
+    for (row, name) in names.iter().enumerate() {
+        print!("{:width$}", name, width = width);
+        for column in 0..engines.len() {
+            let record = pairings.iter().find_map(|pairing| {
+                if (pairing.first, pairing.second) == (row, column) {
+                    Some(pairing.record)
+                } else if (pairing.first, pairing.second) == (column, row) {
+                    Some(pairing.record.reversed())
+                } else {
+                    None
+                }
+            });
+            let cell = match record {
+                Some(record) => format!("{}-{}-{}", record.wins, record.draws, record.losses),
+                None => "-".to_string(),
+            };
+            print!(" {:>width$}", cell, width = width);
+        }
+        println!();
+    }
+
+    let ratings = arena::elo_ratings(engines.len(), &pairings);
+    let mut standings = (0..engines.len()).collect::<Vec<_>>();
+    standings.sort_by(|a, b| ratings[*b].total_cmp(&ratings[*a]));
+
+    println!();
+    println!(
+        "{:width$} {:>6} {:>6} {:>6} {:>6} {:>7} {:>6}",
+        "engine",
+        "games",
+        "wins",
+        "draws",
+        "losses",
+        "score",
+        "elo",
+        width = width
+    );
+    for engine in standings {
+        let mut total = Record::default();
+        for pairing in &pairings {
+            let record = if pairing.first == engine {
+                pairing.record
+            } else if pairing.second == engine {
+                pairing.record.reversed()
+            } else {
+                continue;
+            };
+            total.wins += record.wins;
+            total.draws += record.draws;
+            total.losses += record.losses;
+        }
+
+        println!(
+            "{:width$} {:>6} {:>6} {:>6} {:>6} {:>6.1}% {:>+6.0}",
+            names[engine],
+            total.games(),
+            total.wins,
+            total.draws,
+            total.losses,
+            total.score() * 100.0,
+            ratings[engine],
+            width = width
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! Compares the search on one thread with the parallel root search.
+//!
+//! ```text
+//! cargo run --release -p quarto-core --bin bench_search -- --threads 1,2,4,8,16 --depth 5
+//! ```
+//!
+//! The positions come from `--games` games played by a shallow seeded search,
+//! keeping those with at most `--pieces` pieces left to place, so every run
+//! searches the same positions. Each position is searched to `--depth` from an
+//! empty transposition table, once per thread count. The speedup is against the
+//! first thread count.
+
+use std::{
+    env, process,
+    time::{Duration, Instant},
+};
+
+use quarto_core::{
+    cli::arg, search::CompoundMove, transposition::DEFAULT_TABLE_SIZE_MB, Game, Move, Position,
+    Search,
+};
+
+// Depth of the search playing the games the positions come from
+const PLAY_DEPTH: u32 = 2;
+
+fn positions(games: u64, pieces: usize) -> Vec<Position> {
+    let mut positions = vec![];
+
+    for seed in 0..games {
+        let mut game = Game::init(1);
+        let mut search = Search::with_seed(seed, DEFAULT_TABLE_SIZE_MB);
+
+        while !game.is_over() {
+            let position = game.position();
+            if (2..=pieces).contains(&position.available_pieces.len()) {
+                positions.push(position.clone());
+            }
+
+            let CompoundMove { place, give } = search.run(&position, PLAY_DEPTH).best;
+            let moves = place.map(Move::Place).into_iter().chain(give.map(Move::Give));
+            for mv in moves {
+                if !game.is_over() {
+                    game.apply(mv).expect("the search plays legally");
+                }
+            }
+        }
+    }
+
+    positions
+}
+
+fn main() {
+    let args = env::args().collect::<Vec<_>>();
+    let games = arg(&args, "--games", 4u64);
+    let pieces = arg(&args, "--pieces", 11usize);
+    let depth = arg(&args, "--depth", 5u32);
+    let threads = arg(&args, "--threads", "1,2,4,8,16".to_string())
+        .split(',')
+        .map(|threads| threads.trim().parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_or_else(|_| {
+            eprintln!("--threads expects thread counts separated by commas");
+            process::exit(2);
+        });
+
+    let positions = positions(games, pieces);
+    println!(
+        "{} positions, depth {}, {} cores",
+        positions.len(),
+        depth,
+        rayon::current_num_threads()
+    );
+    println!(
+        "{:>7} {:>12} {:>10} {:>12} {:>8}",
+        "threads", "nodes", "time ms", "nodes/s", "speedup"
+    );
+
+    let mut sequential_time = None;
+    for threads in threads {
+        let mut nodes = 0;
+        let mut time = Duration::ZERO;
+
+        for position in &positions {
+            let mut search = Search::default();
+            search.set_threads(threads);
+
+            let started = Instant::now();
+            nodes += search.run(position, depth).nodes;
+            time += started.elapsed();
+        }
+
+        let baseline = *sequential_time.get_or_insert(time);
+        println!(
+            "{:>7} {:>12} {:>10} {:>12.0} {:>7.2}x",
+            threads,
+            nodes,
+            time.as_millis(),
+            nodes as f64 / time.as_secs_f64(),
+            baseline.as_secs_f64() / time.as_secs_f64()
+        );
+    }
+}
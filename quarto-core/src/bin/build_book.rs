@@ -0,0 +1,131 @@
+//! Builds an opening book from self-play.
+//!
+//! ```text
+//! cargo run --release -p quarto-core --bin build_book -- --games 200 --plies 4 --output assets/opening_book.txt
+//! ```
+//!
+//! Every compound move of the first `--plies` turns of each game is added to the
+//! book, weighted 2 when the side that played it won and 1 on a draw. Moves of
+//! the losing side are left out. With `--input`, the games are added to an
+//! existing book.
+
+use std::{env, process, time::Duration};
+
+use quarto_core::{
+    book::OpeningBook, cli::arg, search::CompoundMove, Difficulty, Game, GameOutcome, Move, Phase,
+    Position, Turn, AI,
+};
+
+const WIN_WEIGHT: u32 = 2;
+const DRAW_WEIGHT: u32 = 1;
+
+/// Plays one game between two AIs. Returns the compound moves played, each with
+/// the position it was played in and the side that played it, and the outcome.
+fn self_play(
+    level: usize,
+    seed: u64,
+    time_budget: Duration,
+) -> (Vec<(Position, CompoundMove, Turn)>, GameOutcome) {
+    let mut game = Game::init(level);
+    let mut player = AI::with_seed(level, seed);
+    let mut opponent = AI::with_seed(level, seed.wrapping_mul(31).wrapping_add(17));
+    player.time_budget = time_budget;
+    opponent.time_budget = time_budget;
+
+    let mut record = vec![];
+    while !game.is_over() {
+        let position = game.position();
+        let by = game.turn();
+        let ai = match by {
+            Turn::Player => &mut player,
+            Turn::Opponent => &mut opponent,
+        };
+
+        let mv = match position.phase {
+            Phase::ChoosePieceForOpponent { .. } => CompoundMove {
+                place: None,
+                give: Some(ai.select_piece(&position)),
+            },
+            Phase::PlacePiece { .. } => {
+                let coor = ai.select_place(&position);
+                game.apply(Move::Place(coor))
+                    .expect("the AI places legally");
+                let give = if game.is_over() {
+                    None
+                } else {
+                    Some(ai.select_piece(&game.position()))
+                };
+                CompoundMove {
+                    place: Some(coor),
+                    give,
+                }
+            }
+        };
+
+        if let Some(piece) = mv.give {
+            game.apply(Move::Give(piece)).expect("the AI gives legally");
+        }
+        record.push((position, mv, by));
+    }
+
+    (record, game.outcome)
+}
+
+fn main() {
+    let args = env::args().collect::<Vec<_>>();
+    let games = arg(&args, "--games", 100u64);
+    let plies = arg(&args, "--plies", 4usize);
+    let seed = arg(&args, "--seed", 1u64);
+    let level = arg(&args, "--level", Difficulty::Expert.level());
+    let time_budget = Duration::from_millis(arg(&args, "--time", 50u64));
+    let output = arg(&args, "--output", "opening_book.txt".to_string());
+    let input = arg(&args, "--input", String::new());
+
+    let mut book = if input.is_empty() {
+        OpeningBook::new()
+    } else {
+        OpeningBook::load(&input).unwrap_or_else(|error| {
+            eprintln!("cannot load {}: {}", input, error);
+            process::exit(1);
+        })
+    };
+
+    let mut results = [0; 3];
+    for game in 0..games {
+        let (record, outcome) = self_play(level, seed.wrapping_add(game), time_budget);
+
+        let winner = match outcome {
+            GameOutcome::Won { by, .. } => Some(by),
+            _ => None,
+        };
+        match winner {
+            Some(Turn::Player) => results[0] += 1,
+            None => results[1] += 1,
+            Some(Turn::Opponent) => results[2] += 1,
+        }
+
+        for (position, mv, by) in record.into_iter().take(plies) {
+            let weight = match winner {
+                Some(winner) if winner == by => WIN_WEIGHT,
+                Some(_) => continue,
+                None => DRAW_WEIGHT,
+            };
+            book.add(&position, mv, weight);
+        }
+
+        println!(
+            "game {}/{}: player {} / draw {} / opponent {}",
+            game + 1,
+            games,
+            results[0],
+            results[1],
+            results[2]
+        );
+    }
+
+    if let Err(error) = book.save(&output) {
+        eprintln!("cannot save {}: {}", output, error);
+        process::exit(1);
+    }
+    println!("{} positions written to {}", book.len(), output);
+}
@@ -0,0 +1,42 @@
+//! The AI as an engine process speaking the `protocol` on its standard input
+//! and output.
+//!
+//! ```text
+//! cargo run --release -p quarto-core --bin engine -- --level expert --book assets/opening_book.txt
+//! ```
+//!
+//! `--level` takes the name or the number of a difficulty, `Expert` by default.
+//! `--threads` sets the threads to search on, every core by default.
+
+use std::{env, io, process, sync::Arc};
+
+use quarto_core::{book::OpeningBook, cli::arg, protocol, Difficulty, AI};
+
+fn main() {
+    let args = env::args().collect::<Vec<_>>();
+    let level = arg(&args, "--level", Difficulty::Expert.name().to_string());
+    let difficulty = Difficulty::parse(&level).unwrap_or_else(|| {
+        eprintln!("unknown level `{}`", level);
+        process::exit(2);
+    });
+    let book = arg(&args, "--book", String::new());
+    let threads = arg(&args, "--threads", rayon::current_num_threads());
+
+    let mut ai = AI::init(difficulty.level());
+    ai.set_threads(threads);
+    if !book.is_empty() {
+        match OpeningBook::load(&book) {
+            Ok(book) => ai.book = Some(Arc::new(book)),
+            Err(e) => {
+                eprintln!("cannot load {}: {}", book, e);
+                process::exit(1);
+            }
+        }
+    }
+
+    let name = format!("quarto-core AI {}", difficulty);
+    if let Err(e) = protocol::serve(ai, &name, io::stdin(), io::stdout()) {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::piece::Piece;
+
+pub const BOARD_ROWS_NUM: usize = 4;
+pub const BOARD_COLUMNS_NUM: usize = 4;
+pub const BOARD_CELLS_NUM: usize = BOARD_ROWS_NUM * BOARD_COLUMNS_NUM;
+
+// Cell indices of each `Line`, in the order of `Line::all`.
+const LINE_CELLS: [[usize; 4]; 10] = [
+    [0, 1, 2, 3],
+    [4, 5, 6, 7],
+    [8, 9, 10, 11],
+    [12, 13, 14, 15],
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [3, 6, 9, 12],
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Coordinate {
+    pub row: i8,
+    pub col: i8,
+}
+
+impl Coordinate {
+    pub fn new(row: i8, col: i8) -> Self {
+        Self { row, col }
+    }
+
+    /// Row-major index of the cell, `0..BOARD_CELLS_NUM`.
+    pub fn index(self) -> usize {
+        self.row as usize * BOARD_COLUMNS_NUM + self.col as usize
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self {
+            row: (index / BOARD_COLUMNS_NUM) as i8,
+            col: (index % BOARD_COLUMNS_NUM) as i8,
+        }
+    }
+
+    pub fn is_on_board(self) -> bool {
+        (0..BOARD_ROWS_NUM as i8).contains(&self.row)
+            && (0..BOARD_COLUMNS_NUM as i8).contains(&self.col)
+    }
+}
+
+/// One of the ten lines of four cells that can complete a Quarto.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Line {
+    Row(i8),
+    Col(i8),
+    DiagonalLtRb,
+    DiagonalRtLb,
+}
+
+impl Line {
+    pub fn all() -> [Line; 10] {
+        [
+            Line::Row(0),
+            Line::Row(1),
+            Line::Row(2),
+            Line::Row(3),
+            Line::Col(0),
+            Line::Col(1),
+            Line::Col(2),
+            Line::Col(3),
+            Line::DiagonalLtRb,
+            Line::DiagonalRtLb,
+        ]
+    }
+
+    pub fn coords(self) -> [Coordinate; 4] {
+        [0, 1, 2, 3].map(|i| match self {
+            Line::Row(n) => Coordinate::new(n, i),
+            Line::Col(n) => Coordinate::new(i, n),
+            Line::DiagonalLtRb => Coordinate::new(i, i),
+            Line::DiagonalRtLb => Coordinate::new(i, 3 - i),
+        })
+    }
+}
+
+/// The 4x4 grid of cells, each either empty or holding a piece.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Board {
+    cells: [Option<Piece>; BOARD_CELLS_NUM],
+}
+
+impl Board {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, coor: Coordinate) -> Option<Piece> {
+        self.cells[coor.index()]
+    }
+
+    pub fn set(&mut self, coor: Coordinate, piece: Option<Piece>) {
+        self.cells[coor.index()] = piece;
+    }
+
+    pub fn is_free(&self, coor: Coordinate) -> bool {
+        self.get(coor).is_none()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.cells.iter().all(|cell| cell.is_some())
+    }
+
+    /// Every cell in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinate, Option<Piece>)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| (Coordinate::from_index(index), *cell))
+    }
+
+    /// Whether placing `piece` on the free cell `coor` completes a Quarto.
+    pub fn is_winning_placement(&self, coor: Coordinate, piece: Piece) -> bool {
+        let index = coor.index();
+
+        LINE_CELLS
+            .iter()
+            .filter(|cells| cells.contains(&index))
+            .any(|cells| {
+                let mut all_set = piece.bits();
+                let mut all_clear = !piece.bits();
+                for cell in cells.iter().filter(|cell| **cell != index) {
+                    match self.cells[*cell] {
+                        Some(other) => {
+                            all_set &= other.bits();
+                            all_clear &= !other.bits();
+                        }
+                        None => return false,
+                    }
+                }
+                (all_set | all_clear) & Piece::ALL_ATTRIBUTES != 0
+            })
+    }
+
+    /// Empty cells in row-major order.
+    pub fn free_coords(&self) -> impl Iterator<Item = Coordinate> + '_ {
+        self.iter()
+            .filter(|(_, cell)| cell.is_none())
+            .map(|(coor, _)| coor)
+    }
+}
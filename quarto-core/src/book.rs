@@ -0,0 +1,290 @@
+//! Opening book: weighted moves for positions met early in the game.
+//!
+//! A book file is plain text. After the format version, each line is a
+//! position, a compound move from it and the weight of that move, separated by
+//! `;`, in the `notation` format:
+//!
+//! ```text
+//! quarto-book 1
+//! 4/4/4/4 LRTS,LRTH,... - P ; LRSS ; 12
+//! 4/4/4/4 LRTS,LRTH,... LRSS O ; a1 DSSH ; 7
+//! ```
+//!
+//! Lines starting with `#` are comments. Positions are looked up by
+//! `symmetry::canonical_hash` and moves by the canonical hash of the position
+//! they lead to, so one line covers every position equivalent to the one
+//! written.
+
+use std::{collections::BTreeMap, error, fmt, fs, io, path::Path};
+
+use crate::{
+    notation::NotationError,
+    position::Position,
+    search::{position_moves, CompoundMove},
+    symmetry,
+};
+
+pub const BOOK_FORMAT_VERSION: u32 = 1;
+
+const BOOK_FORMAT_TAG: &str = "quarto-book";
+
+#[derive(Debug)]
+pub enum BookError {
+    Io(io::Error),
+    UnsupportedVersion(u32),
+    /// Line `line`, counting from 1, cannot be read.
+    Malformed {
+        line: usize,
+        reason: String,
+    },
+    Notation {
+        line: usize,
+        error: NotationError,
+    },
+}
+
+impl fmt::Display for BookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BookError::Io(error) => write!(f, "{}", error),
+            BookError::UnsupportedVersion(version) => write!(
+                f,
+                "book format version {} is not supported (expected {})",
+                version, BOOK_FORMAT_VERSION
+            ),
+            BookError::Malformed { line, reason } => {
+                write!(f, "malformed book, line {}: {}", line, reason)
+            }
+            BookError::Notation { line, error } => write!(f, "book line {}: {}", line, error),
+        }
+    }
+}
+
+impl error::Error for BookError {}
+
+impl From<io::Error> for BookError {
+    fn from(error: io::Error) -> Self {
+        BookError::Io(error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BookMove {
+    mv: CompoundMove,
+    weight: u32,
+    /// Canonical hash of the position `mv` leads to.
+    successor: u64,
+}
+
+#[derive(Debug, Clone)]
+struct BookEntry {
+    /// The position as first added; the moves are written from it.
+    position: Position,
+    moves: Vec<BookMove>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OpeningBook {
+    entries: BTreeMap<u64, BookEntry>,
+}
+
+// Canonical hash of the position after the legal move `mv`
+fn successor_hash(position: &Position, mv: CompoundMove) -> u64 {
+    let mut board = position.board;
+    if let (Some(coor), Some(piece)) = (mv.place, position.piece_in_hand()) {
+        board.set(coor, Some(piece));
+    }
+    symmetry::canonical_hash(&board, mv.give)
+}
+
+impl OpeningBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of positions in the book.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Adds `weight` to `mv` in `position`, or to the equivalent move of an
+    /// equivalent position already in the book. Returns `false`, leaving the
+    /// book untouched, when `mv` is not legal in `position`.
+    pub fn add(&mut self, position: &Position, mv: CompoundMove, weight: u32) -> bool {
+        if !position_moves(position).contains(&mv) {
+            return false;
+        }
+        let successor = successor_hash(position, mv);
+
+        let entry = self
+            .entries
+            .entry(position.canonical_hash())
+            .or_insert_with(|| BookEntry {
+                position: position.clone(),
+                moves: vec![],
+            });
+
+        match entry
+            .moves
+            .iter_mut()
+            .find(|each| each.successor == successor)
+        {
+            Some(existing) => existing.weight += weight,
+            None => {
+                // Written from the position kept in the entry, which may be a
+                // different one of the equivalent positions
+                let mv = if entry.position == *position {
+                    Some(mv)
+                } else {
+                    position_moves(&entry.position)
+                        .into_iter()
+                        .find(|each| successor_hash(&entry.position, *each) == successor)
+                };
+
+                match mv {
+                    Some(mv) => entry.moves.push(BookMove {
+                        mv,
+                        weight,
+                        successor,
+                    }),
+                    None => return false,
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Book moves for `position`, written for `position` itself, with their
+    /// weights. Empty when the position is not in the book.
+    pub fn moves(&self, position: &Position) -> Vec<(CompoundMove, u32)> {
+        let entry = match self.entries.get(&position.canonical_hash()) {
+            Some(entry) => entry,
+            None => return vec![],
+        };
+
+        let mut moves = vec![];
+        let mut seen = vec![];
+        for mv in position_moves(position) {
+            let successor = successor_hash(position, mv);
+
+            // Symmetric moves lead to the same position: keep the first one
+            if seen.contains(&successor) {
+                continue;
+            }
+            seen.push(successor);
+
+            if let Some(book_move) = entry
+                .moves
+                .iter()
+                .find(|each| each.successor == successor && each.weight > 0)
+            {
+                moves.push((mv, book_move.weight));
+            }
+        }
+        moves
+    }
+
+    /// Picks one of the book moves for `position`, with a chance proportional
+    /// to its weight. `roll` is a random number, any `u32`.
+    pub fn choose(&self, position: &Position, roll: u32) -> Option<CompoundMove> {
+        let moves = self.moves(position);
+        let total = moves.iter().map(|(_, weight)| *weight as u64).sum::<u64>();
+        if total == 0 {
+            return None;
+        }
+
+        let mut target = roll as u64 % total;
+        for (mv, weight) in moves {
+            if target < weight as u64 {
+                return Some(mv);
+            }
+            target -= weight as u64;
+        }
+        None
+    }
+
+    pub fn write(&self) -> String {
+        let mut text = format!("{} {}\n", BOOK_FORMAT_TAG, BOOK_FORMAT_VERSION);
+
+        for entry in self.entries.values() {
+            let mut moves = entry.moves.iter().collect::<Vec<_>>();
+            moves.sort_by_key(|each| std::cmp::Reverse(each.weight));
+            for book_move in moves {
+                text.push_str(&format!(
+                    "{} ; {} ; {}\n",
+                    entry.position, book_move.mv, book_move.weight
+                ));
+            }
+        }
+
+        text
+    }
+
+    pub fn read(text: &str) -> Result<Self, BookError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        let version = match lines.next() {
+            Some((_, line)) => line
+                .strip_prefix(BOOK_FORMAT_TAG)
+                .and_then(|version| version.trim().parse::<u32>().ok())
+                .ok_or_else(|| BookError::Malformed {
+                    line: 1,
+                    reason: format!("expected `{} <version>`", BOOK_FORMAT_TAG),
+                })?,
+            None => {
+                return Err(BookError::Malformed {
+                    line: 1,
+                    reason: "empty book".to_string(),
+                })
+            }
+        };
+        if version != BOOK_FORMAT_VERSION {
+            return Err(BookError::UnsupportedVersion(version));
+        }
+
+        let mut book = OpeningBook::new();
+        for (line, text) in lines {
+            let malformed = |reason: &str| BookError::Malformed {
+                line,
+                reason: reason.to_string(),
+            };
+            let notation = |error| BookError::Notation { line, error };
+
+            let fields = text.split(';').map(str::trim).collect::<Vec<_>>();
+            let (position, mv, weight) = match fields[..] {
+                [position, mv, weight] => (position, mv, weight),
+                _ => return Err(malformed("expected `position ; move ; weight`")),
+            };
+
+            let position = position.parse::<Position>().map_err(notation)?;
+            let mv = mv.parse::<CompoundMove>().map_err(notation)?;
+            let weight = weight
+                .parse::<u32>()
+                .map_err(|_| malformed("weight is not a number"))?;
+
+            if !book.add(&position, mv, weight) {
+                return Err(malformed("move is not legal in the position"));
+            }
+        }
+
+        Ok(book)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), BookError> {
+        fs::write(path, self.write())?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BookError> {
+        Self::read(&fs::read_to_string(path)?)
+    }
+}
@@ -0,0 +1,19 @@
+//! Command-line arguments of the binaries in `src/bin`.
+
+use std::{process, str::FromStr};
+
+/// Value following the flag `name` in `args`, or `default` when the flag is
+/// missing. Exits the process when the flag has no value, or one that does not
+/// parse.
+pub fn arg<T: FromStr>(args: &[String], name: &str, default: T) -> T {
+    match args.iter().position(|arg| arg == name) {
+        Some(index) => match args.get(index + 1).map(|value| value.parse()) {
+            Some(Ok(value)) => value,
+            _ => {
+                eprintln!("{} expects a value", name);
+                process::exit(2);
+            }
+        },
+        None => default,
+    }
+}
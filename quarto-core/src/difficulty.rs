@@ -0,0 +1,105 @@
+//! Difficulty levels of the AI, from `Beginner` to `Perfect`. The level of a
+//! game, as saved and passed to `AI::init`, is the number of its difficulty,
+//! from 1 to 6.
+
+use std::{fmt, time::Duration};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Difficulty {
+    Beginner,
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+    Perfect,
+}
+
+/// Engine settings of a difficulty.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Settings {
+    /// Deepest search, in compound moves (place, then give).
+    pub depth: u32,
+    /// Thinking time per move. The search goes deeper until it runs out.
+    pub time_budget: Duration,
+    /// Most the score of each move is randomly raised or lowered by, in
+    /// evaluation units. With noise, every move is scored on its own, as deep
+    /// as the thinking time allows, instead of searched for the best one.
+    pub noise: i32,
+    /// Chance in percent of playing any legal move instead of thinking.
+    pub blunder_percent: u32,
+    /// Plays endgames perfectly with `endgame::solve`.
+    pub solve_endgames: bool,
+    /// Iterations per move of the Monte Carlo tree search opponent.
+    pub mcts_iterations: u32,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 6] = [
+        Difficulty::Beginner,
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+        Difficulty::Perfect,
+    ];
+
+    /// Difficulty of `level`. Levels past either end of the table get the
+    /// difficulty at that end.
+    pub fn from_level(level: usize) -> Self {
+        Self::ALL[level.clamp(1, Self::ALL.len()) - 1]
+    }
+
+    /// Number of the difficulty, from 1 for `Beginner`.
+    pub fn level(self) -> usize {
+        self as usize + 1
+    }
+
+    /// Reads the name of a difficulty, in any case, or its level.
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.parse::<usize>() {
+            Ok(level) if (1..=Self::ALL.len()).contains(&level) => Some(Self::from_level(level)),
+            Ok(_) => None,
+            Err(_) => Self::ALL
+                .into_iter()
+                .find(|difficulty| difficulty.name().eq_ignore_ascii_case(text)),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Beginner => "Beginner",
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+            Difficulty::Expert => "Expert",
+            Difficulty::Perfect => "Perfect",
+        }
+    }
+
+    pub fn settings(self) -> Settings {
+        let (depth, time_budget_ms, noise, blunder_percent, solve_endgames, mcts_iterations) =
+            match self {
+                Difficulty::Beginner => (1, 100, 40, 25, false, 200),
+                Difficulty::Easy => (1, 200, 15, 10, false, 1_000),
+                Difficulty::Medium => (2, 300, 5, 3, false, 4_000),
+                Difficulty::Hard => (4, 500, 0, 0, false, 10_000),
+                Difficulty::Expert => (16, 500, 0, 0, true, 20_000),
+                Difficulty::Perfect => (16, 2000, 0, 0, true, 50_000),
+            };
+
+        Settings {
+            depth,
+            time_budget: Duration::from_millis(time_budget_ms),
+            noise,
+            blunder_percent,
+            solve_endgames,
+            mcts_iterations,
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
@@ -0,0 +1,105 @@
+//! Exact solver for endgames.
+//!
+//! With few pieces left, the search can look all the way to the end of the game
+//! without the static evaluation, so its scores prove the outcome under perfect
+//! play from both sides.
+
+use crate::{
+    position::Position,
+    search::{is_decisive, CompoundMove, Search, WIN_SCORE},
+    transposition::SMALL_TABLE_SIZE_MB,
+};
+
+/// Most pieces left to place, the piece in hand included, for `solve` to take on.
+pub const ENDGAME_PIECES_NUM: usize = 9;
+
+/// Outcome of a position under perfect play, for the side to move.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Verdict {
+    /// The side to move completes a Quarto in its `moves`-th compound move from
+    /// now, whatever the other side does.
+    Win {
+        moves: u32,
+    },
+    /// The other side completes a Quarto in the `moves`-th compound move from
+    /// now, whatever the side to move does.
+    Loss {
+        moves: u32,
+    },
+    Draw,
+}
+
+impl Verdict {
+    fn from_score(score: i32) -> Self {
+        // A win scored `WIN_SCORE - ply` is completed in compound move `ply + 1`
+        let moves = (WIN_SCORE - score.abs() + 1) as u32;
+
+        match score {
+            _ if !is_decisive(score) => {
+                debug_assert_eq!(score, 0, "solved scores are decisive or draws");
+                Verdict::Draw
+            }
+            _ if score > 0 => Verdict::Win { moves },
+            _ => Verdict::Loss { moves },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub verdict: Verdict,
+    pub best: CompoundMove,
+    /// Line of play, starting with `best`, where both sides play perfectly.
+    pub pv: Vec<CompoundMove>,
+    pub nodes: u64,
+}
+
+// Compound moves to the end of the game, whoever is to move
+fn moves_left(position: &Position) -> u32 {
+    position.available_pieces.len() as u32 + 1
+}
+
+fn is_solvable(position: &Position) -> bool {
+    !position.available_pieces.is_empty()
+        && position.available_pieces.len() <= ENDGAME_PIECES_NUM
+        && position.board.free_coords().next().is_some()
+}
+
+/// Solves `position`, or returns `None` when it has more than
+/// `ENDGAME_PIECES_NUM` pieces left or no move to play.
+pub fn solve(position: &Position) -> Option<Solution> {
+    solve_with(&mut Search::new(SMALL_TABLE_SIZE_MB), position)
+}
+
+/// Same as `solve`, sharing the transposition table of `search`.
+pub fn solve_with(search: &mut Search, position: &Position) -> Option<Solution> {
+    if !is_solvable(position) {
+        return None;
+    }
+
+    let result = search.run(position, moves_left(position));
+
+    Some(Solution {
+        verdict: Verdict::from_score(result.score),
+        best: result.best,
+        pv: result.pv,
+        nodes: result.nodes,
+    })
+}
+
+/// Verdict of every compound move of `position`, to check other evaluations
+/// against. `None` under the same conditions as `solve`.
+pub fn analyze(position: &Position) -> Option<Vec<(CompoundMove, Verdict)>> {
+    if !is_solvable(position) {
+        return None;
+    }
+
+    let mut search = Search::new(SMALL_TABLE_SIZE_MB);
+    let moves = search
+        .score_moves(position, moves_left(position))
+        .into_iter()
+        .map(|(mv, score)| (mv, Verdict::from_score(score)))
+        .collect();
+
+    Some(moves)
+}
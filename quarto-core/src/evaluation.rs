@@ -0,0 +1,176 @@
+//! Static evaluation for depth-limited search: the heuristics first sketched as
+//! pseudocode in the client (`get_board_values`, `maximize_property`,
+//! `get_wining_properties` and `eval_position`), over the real board.
+//!
+//! Each line gets a value per attribute: the sum over its pieces of `1` for a
+//! piece with the attribute bit set (Dark, Square, Tall, Hollow) and `-1` for a
+//! piece without it. A value of `3` or `-3` means three pieces share that value
+//! and the fourth cell is free: any piece with the value completes a Quarto there.
+
+use crate::{
+    board::{Board, Coordinate, Line},
+    piece::{Attribute, Piece},
+    position::Position,
+    search::{piece_mask, pieces_in},
+};
+
+/// Attributes in the order of the values of `line_values`.
+pub const ATTRIBUTE_MASKS: [u8; 4] = [Piece::COLOR, Piece::SHAPE, Piece::HEIGHT, Piece::FILL];
+
+/// Score of a placement after which every piece left to give completes a Quarto.
+pub const LOST_PLACEMENT_SCORE: i32 = -50;
+
+/// Value of each attribute, in the order of `ATTRIBUTE_MASKS`, for each line,
+/// in the order of `Line::all`.
+pub fn line_values(board: &Board) -> [[i32; 4]; 10] {
+    let mut values = [[0; 4]; 10];
+
+    for (line, line_values) in Line::all().iter().zip(values.iter_mut()) {
+        for piece in line.coords().iter().filter_map(|coor| board.get(*coor)) {
+            for (value, mask) in line_values.iter_mut().zip(ATTRIBUTE_MASKS) {
+                *value += if piece.bits() & mask != 0 { 1 } else { -1 };
+            }
+        }
+    }
+
+    values
+}
+
+/// Free cell of the line whose value for `ATTRIBUTE_MASKS[attribute]` is the
+/// furthest from zero, the first line in `Line::all` order on a tie. `None` when
+/// no line has a free cell.
+pub fn best_place_for_attribute(
+    board: &Board,
+    values: &[[i32; 4]; 10],
+    attribute: usize,
+) -> Option<Coordinate> {
+    let mut best: Option<(i32, Coordinate)> = None;
+
+    for (line, line_values) in Line::all().iter().zip(values) {
+        let free = match line.coords().into_iter().find(|coor| board.is_free(*coor)) {
+            Some(coor) => coor,
+            None => continue,
+        };
+
+        let strength = line_values[attribute].pow(2);
+        if best.map_or(true, |(best_strength, _)| strength > best_strength) {
+            best = Some((strength, free));
+        }
+    }
+
+    best.map(|(_, coor)| coor)
+}
+
+// Attribute bits that complete a Quarto on some free cell: in `.0` for pieces
+// that have the bit set, in `.1` for pieces that have it clear
+fn winning_masks(board: &Board) -> (u8, u8) {
+    let mut set = 0;
+    let mut clear = 0;
+
+    for values in line_values(board) {
+        for (value, mask) in values.into_iter().zip(ATTRIBUTE_MASKS) {
+            match value {
+                3 => set |= mask,
+                -3 => clear |= mask,
+                _ => {}
+            }
+        }
+    }
+
+    (set, clear)
+}
+
+fn completes_quarto(piece: Piece, (set, clear): (u8, u8)) -> bool {
+    piece.bits() & set != 0 || !piece.bits() & clear != 0
+}
+
+/// Attribute values with which a piece completes a Quarto on some free cell.
+pub fn winning_attributes(board: &Board) -> Vec<Attribute> {
+    let (set, clear) = winning_masks(board);
+
+    ATTRIBUTE_MASKS
+        .into_iter()
+        .flat_map(|mask| {
+            let with = (set & mask != 0).then(|| Piece::from_bits(mask).attribute(mask));
+            let without = (clear & mask != 0).then(|| Piece::from_bits(0).attribute(mask));
+            with.into_iter().chain(without)
+        })
+        .collect()
+}
+
+/// Heuristic value of placing `piece` on the free cell `coor`, for the side
+/// placing it: a point for every attribute for which `coor` is the best place,
+/// and a point for every winning attribute value left on the board. It is `0`
+/// when both values of an attribute are winning, since every piece the other
+/// side can be given then completes a Quarto.
+pub fn eval_placement(board: &Board, coor: Coordinate, piece: Piece) -> i32 {
+    placement_score(board, &line_values(board), coor, piece)
+}
+
+fn placement_score(board: &Board, values: &[[i32; 4]; 10], coor: Coordinate, piece: Piece) -> i32 {
+    let best_places = (0..ATTRIBUTE_MASKS.len())
+        .filter(|attribute| best_place_for_attribute(board, values, *attribute) == Some(coor))
+        .count() as i32;
+
+    let mut after = *board;
+    after.set(coor, Some(piece));
+    let (set, clear) = winning_masks(&after);
+
+    if set & clear != 0 {
+        return 0;
+    }
+
+    best_places + (set.count_ones() + clear.count_ones()) as i32
+}
+
+/// Score of a search node for the side that has to place `piece`, with the
+/// pieces of the `available` mask left to give afterwards: the value of its
+/// best placement, or `LOST_PLACEMENT_SCORE` for a placement that leaves no
+/// safe piece to give.
+pub(crate) fn evaluate_node(board: &Board, piece: Piece, available: u16) -> i32 {
+    let values = line_values(board);
+
+    board
+        .free_coords()
+        .map(|coor| {
+            let mut after = *board;
+            after.set(coor, Some(piece));
+            let masks = winning_masks(&after);
+
+            if available != 0 && pieces_in(available).all(|give| completes_quarto(give, masks)) {
+                LOST_PLACEMENT_SCORE
+            } else {
+                placement_score(board, &values, coor, piece)
+            }
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Static score of `position` for the side to move. When a piece has to be
+/// chosen, the piece that leaves the other side with the lowest score is.
+pub fn evaluate(position: &Position) -> i32 {
+    let board = &position.board;
+
+    match position.piece_in_hand() {
+        Some(piece) => {
+            let available =
+                piece_mask(position.available_pieces.iter().copied()) & !(1 << piece.bits());
+            evaluate_node(board, piece, available)
+        }
+        None => {
+            let available = piece_mask(position.available_pieces.iter().copied());
+            let masks = winning_masks(board);
+            pieces_in(available)
+                .map(|give| {
+                    if completes_quarto(give, masks) {
+                        LOST_PLACEMENT_SCORE
+                    } else {
+                        -evaluate_node(board, give, available & !(1 << give.bits()))
+                    }
+                })
+                .max()
+                .unwrap_or(0)
+        }
+    }
+}
@@ -0,0 +1,387 @@
+use std::{error, fmt};
+
+use crate::{
+    board::{Board, Coordinate, Line},
+    piece::{Attribute, Piece},
+    position::Position,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Turn {
+    Player,
+    Opponent,
+}
+
+impl Turn {
+    pub fn other(self) -> Self {
+        match self {
+            Turn::Player => Turn::Opponent,
+            Turn::Opponent => Turn::Player,
+        }
+    }
+}
+
+/// Where the game stands within a turn. Each turn is made of two steps: placing
+/// the piece received from the other side, then choosing the piece to hand back.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Phase {
+    /// `by` has to choose which of the available pieces the other side places next.
+    ChoosePieceForOpponent { by: Turn },
+    /// `by` has to place `piece`, which was handed over by the other side.
+    PlacePiece { by: Turn, piece: Piece },
+}
+
+/// `Phase` without its data, used to describe which phase a move needs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PhaseKind {
+    ChoosePieceForOpponent,
+    PlacePiece,
+}
+
+impl Phase {
+    /// Side that has to act in this phase.
+    pub fn by(self) -> Turn {
+        match self {
+            Phase::ChoosePieceForOpponent { by } | Phase::PlacePiece { by, .. } => by,
+        }
+    }
+
+    pub fn kind(self) -> PhaseKind {
+        match self {
+            Phase::ChoosePieceForOpponent { .. } => PhaseKind::ChoosePieceForOpponent,
+            Phase::PlacePiece { .. } => PhaseKind::PlacePiece,
+        }
+    }
+}
+
+/// One step of a turn, as submitted by the keyboard UI or an AI.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Move {
+    /// Hand the piece to the other side.
+    Give(Piece),
+    /// Place the piece in hand on the cell.
+    Place(Coordinate),
+}
+
+impl Move {
+    pub fn phase_kind(self) -> PhaseKind {
+        match self {
+            Move::Give(_) => PhaseKind::ChoosePieceForOpponent,
+            Move::Place(_) => PhaseKind::PlacePiece,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameError {
+    /// The move belongs to the `expected` phase, but the game is in `actual`.
+    WrongPhase { expected: PhaseKind, actual: Phase },
+    GameOver,
+    CellOccupied(Coordinate),
+    OutOfBoard(Coordinate),
+    PieceNotAvailable(Piece),
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::WrongPhase { expected, actual } => write!(
+                f,
+                "move needs phase {:?}, but the game is in {:?}",
+                expected, actual
+            ),
+            GameError::GameOver => write!(f, "the game is already over"),
+            GameError::CellOccupied(coor) => {
+                write!(f, "cell ({}, {}) is already occupied", coor.row, coor.col)
+            }
+            GameError::OutOfBoard(coor) => {
+                write!(f, "cell ({}, {}) is not on the board", coor.row, coor.col)
+            }
+            GameError::PieceNotAvailable(piece) => {
+                write!(f, "piece {} is not available", piece)
+            }
+        }
+    }
+}
+
+impl error::Error for GameError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameOutcome {
+    InProgress,
+    /// `by` completed `line`, made of the cells `coords`, whose pieces all share
+    /// every value in `attributes`. When one placement completes several lines,
+    /// only the first of them in the order of `Line::all` is reported.
+    Won {
+        by: Turn,
+        line: Line,
+        coords: [Coordinate; 4],
+        attributes: Vec<Attribute>,
+    },
+    /// All sixteen cells are filled without a Quarto.
+    Draw,
+}
+
+#[derive(Debug, Clone)]
+pub struct Game {
+    pub phase: Phase,
+    pub available_pieces: Vec<Piece>,
+    pub board: Board,
+    /// Level the AI players of the game are set to.
+    pub level: usize,
+    pub outcome: GameOutcome,
+    /// Position the history was played from.
+    start: Position,
+    history: Vec<Move>,
+    undone: Vec<Move>,
+}
+
+impl Game {
+    pub fn init(level: usize) -> Self {
+        Game::from_position(level, Position::initial())
+    }
+
+    /// Starts a game from `position`, without any history to undo. Undo goes
+    /// back as far as `position`.
+    pub fn from_position(level: usize, position: Position) -> Self {
+        let mut game = Self {
+            phase: position.phase,
+            available_pieces: position.available_pieces.clone(),
+            board: position.board,
+            level,
+            outcome: GameOutcome::InProgress,
+            start: position,
+            history: vec![],
+            undone: vec![],
+        };
+        game.outcome = game.check_game_state(game.start.last_placed_by());
+        game
+    }
+
+    pub fn position(&self) -> Position {
+        Position {
+            board: self.board,
+            available_pieces: self.available_pieces.clone(),
+            phase: self.phase,
+        }
+    }
+
+    /// Builds a game by playing `moves` from the starting position.
+    pub fn from_moves(level: usize, moves: &[Move]) -> Result<Self, GameError> {
+        Game::from_position_and_moves(level, Position::initial(), moves)
+    }
+
+    /// Builds a game by playing `moves` from `position`.
+    pub fn from_position_and_moves(
+        level: usize,
+        position: Position,
+        moves: &[Move],
+    ) -> Result<Self, GameError> {
+        let mut game = Game::from_position(level, position);
+        for mv in moves {
+            game.apply(*mv)?;
+        }
+        Ok(game)
+    }
+
+    /// Position the game was started from, before any move of its history.
+    pub fn start(&self) -> &Position {
+        &self.start
+    }
+
+    pub fn reset(&mut self, level: usize) {
+        *self = Game::init(level);
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.outcome != GameOutcome::InProgress
+    }
+
+    /// Side that has to act next.
+    pub fn turn(&self) -> Turn {
+        self.phase.by()
+    }
+
+    /// Piece waiting to be placed, if any.
+    pub fn piece_in_hand(&self) -> Option<Piece> {
+        match self.phase {
+            Phase::PlacePiece { piece, .. } => Some(piece),
+            Phase::ChoosePieceForOpponent { .. } => None,
+        }
+    }
+
+    pub fn has_same_feature(&self, pieces: &[Piece]) -> bool {
+        Piece::shared_attributes(pieces) != 0
+    }
+
+    fn line_pieces(&self, line: Line) -> Vec<Piece> {
+        line.coords()
+            .iter()
+            .filter_map(|coor| self.board.get(*coor))
+            .collect()
+    }
+
+    /// Attribute values shared by the four pieces of a full `line`, or `None` if
+    /// the line is not a Quarto.
+    pub fn check_line(&self, line: Line) -> Option<Vec<Attribute>> {
+        let pieces = self.line_pieces(line);
+
+        if pieces.len() < 4 || !self.has_same_feature(&pieces) {
+            return None;
+        }
+
+        Some(pieces[0].attributes(Piece::shared_attributes(&pieces)))
+    }
+
+    pub fn check_row(&self, n: i8) -> Option<Vec<Attribute>> {
+        self.check_line(Line::Row(n))
+    }
+
+    pub fn check_col(&self, n: i8) -> Option<Vec<Attribute>> {
+        self.check_line(Line::Col(n))
+    }
+
+    pub fn check_diagonal_lt_rb(&self) -> Option<Vec<Attribute>> {
+        self.check_line(Line::DiagonalLtRb)
+    }
+
+    pub fn check_diagonal_rt_lb(&self) -> Option<Vec<Attribute>> {
+        self.check_line(Line::DiagonalRtLb)
+    }
+
+    /// Outcome of the current board, crediting a completed line to `by`. Only
+    /// the first completed line in the order of `Line::all` is reported.
+    pub fn check_game_state(&self, by: Turn) -> GameOutcome {
+        let winning_line = Line::all()
+            .into_iter()
+            .find_map(|line| self.check_line(line).map(|attributes| (line, attributes)));
+
+        match winning_line {
+            Some((line, attributes)) => GameOutcome::Won {
+                by,
+                line,
+                coords: line.coords(),
+                attributes,
+            },
+            None if self.board.is_full() => GameOutcome::Draw,
+            None => GameOutcome::InProgress,
+        }
+    }
+
+    /// Checks `mv` against the rules and plays it. The game is left untouched
+    /// when the move is rejected.
+    pub fn apply(&mut self, mv: Move) -> Result<(), GameError> {
+        self.play(mv)?;
+        self.undone.clear();
+
+        Ok(())
+    }
+
+    /// Every move played so far, oldest first. Moves alternate between the two
+    /// steps of a turn, so the side of each move follows from its position.
+    pub fn history(&self) -> &[Move] {
+        &self.history
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.history.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// Moves taken back by `undo`, in the order `redo` would play them again.
+    pub fn redo_moves(&self) -> Vec<Move> {
+        self.undone.iter().rev().copied().collect()
+    }
+
+    /// Takes back the last move, restoring the board, the available pieces, the
+    /// phase and the outcome. Returns the move taken back.
+    pub fn undo(&mut self) -> Option<Move> {
+        let mv = self.history.pop()?;
+        self.undone.push(mv);
+
+        let moves = std::mem::take(&mut self.history);
+        self.board = self.start.board;
+        self.available_pieces = self.start.available_pieces.clone();
+        self.phase = self.start.phase;
+        self.outcome = self.check_game_state(self.start.last_placed_by());
+        for mv in moves {
+            self.play(mv).expect("moves in the history are legal");
+        }
+
+        Some(mv)
+    }
+
+    /// Plays again the last move taken back by `undo`.
+    pub fn redo(&mut self) -> Option<Move> {
+        let mv = self.undone.pop()?;
+        self.play(mv).expect("undone moves are legal where they were taken back");
+
+        Some(mv)
+    }
+
+    fn play(&mut self, mv: Move) -> Result<(), GameError> {
+        self.validate(mv)?;
+
+        match (mv, self.phase) {
+            (Move::Give(piece), Phase::ChoosePieceForOpponent { by }) => {
+                self.phase = Phase::PlacePiece {
+                    by: by.other(),
+                    piece,
+                };
+            }
+            (Move::Place(coor), Phase::PlacePiece { by, piece }) => {
+                self.board.set(coor, Some(piece));
+                self.available_pieces.retain(|each| *each != piece);
+
+                self.outcome = self.check_game_state(by);
+                self.phase = Phase::ChoosePieceForOpponent { by };
+            }
+            _ => unreachable!("validate rejects moves of the wrong phase"),
+        }
+        self.history.push(mv);
+
+        Ok(())
+    }
+
+    /// Checks whether `mv` could be applied now, without playing it.
+    pub fn validate(&self, mv: Move) -> Result<(), GameError> {
+        if self.is_over() {
+            return Err(GameError::GameOver);
+        }
+
+        if mv.phase_kind() != self.phase.kind() {
+            return Err(GameError::WrongPhase {
+                expected: mv.phase_kind(),
+                actual: self.phase,
+            });
+        }
+
+        match mv {
+            Move::Give(piece) if !self.available_pieces.contains(&piece) => {
+                Err(GameError::PieceNotAvailable(piece))
+            }
+            Move::Place(coor) if !coor.is_on_board() => Err(GameError::OutOfBoard(coor)),
+            Move::Place(coor) if !self.board.is_free(coor) => Err(GameError::CellOccupied(coor)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Every move that `apply` would accept now.
+    pub fn legal_moves(&self) -> Vec<Move> {
+        if self.is_over() {
+            return vec![];
+        }
+
+        match self.phase {
+            Phase::ChoosePieceForOpponent { .. } => self
+                .available_pieces
+                .iter()
+                .map(|piece| Move::Give(*piece))
+                .collect(),
+            Phase::PlacePiece { .. } => self.board.free_coords().map(Move::Place).collect(),
+        }
+    }
+}
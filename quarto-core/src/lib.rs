@@ -0,0 +1,36 @@
+//! Rules, board state and AI for the Quarto board game.
+//!
+//! This crate has no graphics or windowing dependencies, so the game logic can be
+//! driven and tested headlessly. The wgpu client reads the state kept here and draws it.
+
+pub mod ai;
+pub mod analysis;
+pub mod arena;
+pub mod board;
+pub mod book;
+pub mod cli;
+pub mod difficulty;
+pub mod endgame;
+pub mod evaluation;
+pub mod game;
+pub mod mcts;
+pub mod notation;
+pub mod piece;
+pub mod player;
+pub mod position;
+pub mod protocol;
+pub mod replay;
+pub mod save;
+pub mod search;
+pub mod symmetry;
+pub mod transposition;
+pub mod worker;
+
+pub use ai::AI;
+pub use board::{Board, Coordinate, Line};
+pub use difficulty::Difficulty;
+pub use game::{Game, GameError, GameOutcome, Move, Phase, PhaseKind, Turn};
+pub use piece::{Attribute, Color, Fill, Height, Piece, Shape, PIECE_NAMES};
+pub use player::{Player, Players};
+pub use position::Position;
+pub use search::{CompoundMove, Search, SearchResult};
@@ -0,0 +1,350 @@
+//! Monte Carlo tree search opponent.
+//!
+//! The tree holds compound moves, as in `search`. Each iteration walks down the
+//! tree choosing children by UCT, adds one new child, plays the game out from it
+//! and credits the result to every move on the way back up. The move played is
+//! the most visited child of the root.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use crate::{
+    board::{Board, Coordinate},
+    difficulty::Difficulty,
+    piece::Piece,
+    position::Position,
+    search::{compound_moves, piece_mask, pieces_in, CompoundMove},
+};
+
+// UCT exploration constant, for rewards between 0 and 1
+const EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+const WIN_REWARD: f64 = 1.0;
+const DRAW_REWARD: f64 = 0.5;
+
+/// How games are played out from a new leaf.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Playout {
+    /// Every placement and every piece given is random.
+    Random,
+    /// Completes a Quarto when it can and avoids giving away a piece that
+    /// completes one; random otherwise.
+    Heuristic,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Ending {
+    /// The side that moved completed a Quarto.
+    Win,
+    Draw,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct State {
+    board: Board,
+    piece: Option<Piece>,
+    /// Pieces left to hand over, not counting the piece in hand.
+    available: u16,
+}
+
+impl State {
+    fn from_position(position: &Position) -> Self {
+        let piece = position.piece_in_hand();
+        let available = piece_mask(position.available_pieces.iter().copied())
+            & !piece.map_or(0, |piece| 1 << piece.bits());
+
+        Self {
+            board: position.board,
+            piece,
+            available,
+        }
+    }
+
+    fn play(&self, mv: CompoundMove) -> (State, Option<Ending>) {
+        let mut next = *self;
+
+        if let (Some(coor), Some(piece)) = (mv.place, self.piece) {
+            if self.board.is_winning_placement(coor, piece) {
+                return (next, Some(Ending::Win));
+            }
+            next.board.set(coor, Some(piece));
+        }
+
+        match mv.give {
+            Some(give) => {
+                next.piece = Some(give);
+                next.available &= !(1 << give.bits());
+                (next, None)
+            }
+            None => (next, Some(Ending::Draw)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TreeNode {
+    mv: Option<CompoundMove>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    untried: Vec<CompoundMove>,
+    visits: u32,
+    /// Sum of the rewards of the side that played `mv`.
+    reward: f64,
+    ending: Option<Ending>,
+}
+
+impl TreeNode {
+    fn new(
+        mv: Option<CompoundMove>,
+        parent: Option<usize>,
+        state: &State,
+        ending: Option<Ending>,
+    ) -> Self {
+        let mut untried = match ending {
+            Some(_) => vec![],
+            None => compound_moves(&state.board, state.piece, state.available),
+        };
+
+        // A side that can complete a Quarto does, so its other moves are not
+        // worth visiting: without this, a piece that lets the other side win
+        // looks as good as the rare replies that miss the win
+        if let Some(piece) = state.piece {
+            let win = untried.iter().copied().find(|mv| {
+                mv.place
+                    .is_some_and(|coor| state.board.is_winning_placement(coor, piece))
+            });
+            if let Some(win) = win {
+                untried = vec![win];
+            }
+        }
+
+        Self {
+            mv,
+            parent,
+            children: vec![],
+            untried,
+            visits: 0,
+            reward: 0.0,
+            ending,
+        }
+    }
+
+    fn uct(&self, parent_visits: u32) -> f64 {
+        self.reward / self.visits as f64
+            + EXPLORATION * ((parent_visits as f64).ln() / self.visits as f64).sqrt()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Mcts {
+    pub iterations: u32,
+    pub playout: Playout,
+    rng: oorandom::Rand32,
+    /// Piece the last placement search meant to hand over next, with the board
+    /// it was meant for.
+    planned_give: Option<(Board, Piece)>,
+    stop: Option<Arc<AtomicBool>>,
+}
+
+impl Mcts {
+    /// Opponent with the iterations of the difficulty of `level`.
+    pub fn init(level: usize) -> Self {
+        Self::new(Difficulty::from_level(level).settings().mcts_iterations, 4)
+    }
+
+    pub fn new(iterations: u32, seed: u64) -> Self {
+        Self {
+            iterations,
+            playout: Playout::Heuristic,
+            rng: oorandom::Rand32::new(seed),
+            planned_give: None,
+            stop: None,
+        }
+    }
+
+    /// Makes searches end after the current iteration once `stop` is set. The
+    /// move returned then is only fit to be dropped.
+    pub fn set_stop_flag(&mut self, stop: Arc<AtomicBool>) {
+        self.stop = Some(stop);
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.stop
+            .as_ref()
+            .is_some_and(|stop| stop.load(Ordering::Relaxed))
+    }
+
+    /// Chooses the piece to hand over to the other side.
+    pub fn select_piece(&mut self, position: &Position) -> Piece {
+        match self.planned_give.take() {
+            Some((board, piece))
+                if board == position.board && position.available_pieces.contains(&piece) =>
+            {
+                piece
+            }
+            _ => self
+                .run(position)
+                .give
+                .expect("a piece can be given while pieces are available"),
+        }
+    }
+
+    /// Chooses an empty cell of the board to place the piece in hand on.
+    pub fn select_place(&mut self, position: &Position) -> Coordinate {
+        let best = self.run(position);
+        let place = best.place.expect("a cell is free while a piece is in hand");
+
+        let mut board = position.board;
+        board.set(place, position.piece_in_hand());
+        self.planned_give = best.give.map(|piece| (board, piece));
+        place
+    }
+
+    /// Searches `position` for `iterations` iterations and returns the most
+    /// visited compound move, or a move with neither field set when the
+    /// position has none.
+    pub fn run(&mut self, position: &Position) -> CompoundMove {
+        let root_state = State::from_position(position);
+        let mut tree = vec![TreeNode::new(None, None, &root_state, None)];
+
+        if tree[0].untried.is_empty() {
+            return CompoundMove {
+                place: None,
+                give: None,
+            };
+        }
+
+        let mut iterations = 0;
+        while iterations < self.iterations.max(1) {
+            if iterations > 0 && self.is_stopped() {
+                break;
+            }
+            iterations += 1;
+
+            // Selection
+            let mut node = 0;
+            let mut state = root_state;
+            while tree[node].untried.is_empty() && !tree[node].children.is_empty() {
+                let parent_visits = tree[node].visits;
+                node = *tree[node]
+                    .children
+                    .iter()
+                    .max_by(|a, b| {
+                        tree[**a]
+                            .uct(parent_visits)
+                            .total_cmp(&tree[**b].uct(parent_visits))
+                    })
+                    .expect("the node has children");
+                state = state.play(tree[node].mv.expect("children have a move")).0;
+            }
+
+            // Expansion
+            if !tree[node].untried.is_empty() {
+                let index = self.random_index(tree[node].untried.len());
+                let mv = tree[node].untried.swap_remove(index);
+                let (next, ending) = state.play(mv);
+
+                let child = tree.len();
+                tree.push(TreeNode::new(Some(mv), Some(node), &next, ending));
+                tree[node].children.push(child);
+                node = child;
+                state = next;
+            }
+
+            // Simulation, scored for the side that played the move into `node`
+            let mut reward = match tree[node].ending {
+                Some(Ending::Win) => WIN_REWARD,
+                Some(Ending::Draw) => DRAW_REWARD,
+                None => WIN_REWARD - self.play_out(state),
+            };
+
+            // Backpropagation
+            let mut current = Some(node);
+            while let Some(index) = current {
+                tree[index].visits += 1;
+                tree[index].reward += reward;
+                reward = WIN_REWARD - reward;
+                current = tree[index].parent;
+            }
+        }
+
+        let best = tree[0]
+            .children
+            .iter()
+            .map(|child| &tree[*child])
+            .max_by_key(|child| child.visits)
+            .expect("the root has a move");
+
+        log::info!(
+            "mcts {} iterations, best {} visits {} reward {:.3}",
+            iterations,
+            best.mv.expect("children have a move"),
+            best.visits,
+            best.reward / best.visits as f64
+        );
+
+        best.mv.expect("children have a move")
+    }
+
+    /// Plays `state` out to the end. Returns the reward of the side to move.
+    fn play_out(&mut self, mut state: State) -> f64 {
+        let to_move_reward = |side_to_move: bool, reward: f64| {
+            if side_to_move {
+                reward
+            } else {
+                WIN_REWARD - reward
+            }
+        };
+        let mut side_to_move = true;
+
+        loop {
+            if let Some(piece) = state.piece {
+                let free = state.board.free_coords().collect::<Vec<_>>();
+                let winning = free
+                    .iter()
+                    .copied()
+                    .find(|coor| state.board.is_winning_placement(*coor, piece));
+
+                let coor = match (self.playout, winning) {
+                    (Playout::Heuristic, Some(coor)) => coor,
+                    _ => free[self.random_index(free.len())],
+                };
+                if state.board.is_winning_placement(coor, piece) {
+                    return to_move_reward(side_to_move, WIN_REWARD);
+                }
+                state.board.set(coor, Some(piece));
+
+                if state.available == 0 {
+                    return to_move_reward(side_to_move, DRAW_REWARD);
+                }
+            }
+
+            let pieces = pieces_in(state.available).collect::<Vec<_>>();
+            let safe = match self.playout {
+                Playout::Heuristic => pieces
+                    .iter()
+                    .copied()
+                    .filter(|piece| {
+                        !state
+                            .board
+                            .free_coords()
+                            .any(|coor| state.board.is_winning_placement(coor, *piece))
+                    })
+                    .collect::<Vec<_>>(),
+                Playout::Random => vec![],
+            };
+            let choices = if safe.is_empty() { &pieces } else { &safe };
+            let give = choices[self.random_index(choices.len())];
+
+            state.piece = Some(give);
+            state.available &= !(1 << give.bits());
+            side_to_move = !side_to_move;
+        }
+    }
+
+    fn random_index(&mut self, size: usize) -> usize {
+        self.rng.rand_range(0..size as u32) as usize
+    }
+}
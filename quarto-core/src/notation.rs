@@ -0,0 +1,389 @@
+//! Compact text notation for pieces, cells, moves and positions.
+//!
+//! * A piece is four letters, one per attribute in the order of the asset names:
+//!   color `L`ight/`D`ark, shape `R`ound/`S`quare, height `T`all/`S`hort and fill
+//!   `S`olid/`H`ollow. `LRTS` is `Light_Round_Tall_Solid`.
+//! * A cell is a column letter `a`-`d` followed by a row number `1`-`4`: `a1` is
+//!   `Coordinate { row: 0, col: 0 }` and `d2` is `Coordinate { row: 1, col: 3 }`.
+//! * Placing the piece in hand is written `<cell>:<piece>`, e.g. `c3:LRTS`. The
+//!   piece may be left out (`c3`) since it is implied by the position. Handing a
+//!   piece to the other side is written as the piece alone, e.g. `DSSH`.
+//! * A game record is its moves separated by spaces, from the initial position.
+//! * A position is a single line of four space-separated fields:
+//!   1. the board, rows `1` to `4` separated by `/`, where each cell is a piece
+//!      and a digit counts consecutive empty cells, as in chess FEN;
+//!   2. the pieces not on the board yet (including the piece in hand), separated
+//!      by `,`, or `-` when none are left;
+//!   3. the piece in hand, or `-` when a piece has to be chosen;
+//!   4. the side to move, `P` for the player or `O` for the opponent.
+//!
+//!   The initial position is `4/4/4/4 LRTS,LRTH,LSTS,... - P`.
+
+use std::{error, fmt, str::FromStr};
+
+use crate::{
+    board::{Board, Coordinate, BOARD_COLUMNS_NUM, BOARD_ROWS_NUM},
+    game::{Move, Phase, Turn},
+    piece::{Color, Fill, Height, Piece, Shape},
+    position::Position,
+    search::CompoundMove,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotationError {
+    InvalidPiece(String),
+    InvalidCell(String),
+    InvalidMove(String),
+    InvalidPosition(String),
+    /// A placement names a different piece than the one in hand.
+    PieceMismatch { expected: Piece, found: Piece },
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotationError::InvalidPiece(text) => write!(f, "invalid piece `{}`", text),
+            NotationError::InvalidCell(text) => write!(f, "invalid cell `{}`", text),
+            NotationError::InvalidMove(text) => write!(f, "invalid move `{}`", text),
+            NotationError::InvalidPosition(reason) => write!(f, "invalid position: {}", reason),
+            NotationError::PieceMismatch { expected, found } => write!(
+                f,
+                "placement names {} but the piece in hand is {}",
+                format_piece(*found),
+                format_piece(*expected)
+            ),
+        }
+    }
+}
+
+impl error::Error for NotationError {}
+
+pub fn format_piece(piece: Piece) -> String {
+    [
+        if piece.color() == Color::Light { 'L' } else { 'D' },
+        if piece.shape() == Shape::Round { 'R' } else { 'S' },
+        if piece.height() == Height::Tall { 'T' } else { 'S' },
+        if piece.fill() == Fill::Solid { 'S' } else { 'H' },
+    ]
+    .iter()
+    .collect()
+}
+
+pub fn parse_piece(text: &str) -> Result<Piece, NotationError> {
+    let invalid = || NotationError::InvalidPiece(text.to_string());
+
+    let letters = text.chars().collect::<Vec<_>>();
+    if letters.len() != 4 {
+        return Err(invalid());
+    }
+
+    let color = match letters[0] {
+        'L' => Color::Light,
+        'D' => Color::Dark,
+        _ => return Err(invalid()),
+    };
+    let shape = match letters[1] {
+        'R' => Shape::Round,
+        'S' => Shape::Square,
+        _ => return Err(invalid()),
+    };
+    let height = match letters[2] {
+        'T' => Height::Tall,
+        'S' => Height::Short,
+        _ => return Err(invalid()),
+    };
+    let fill = match letters[3] {
+        'S' => Fill::Solid,
+        'H' => Fill::Hollow,
+        _ => return Err(invalid()),
+    };
+
+    Ok(Piece::new(color, shape, height, fill))
+}
+
+pub fn format_coordinate(coor: Coordinate) -> String {
+    format!("{}{}", (b'a' + coor.col as u8) as char, coor.row + 1)
+}
+
+pub fn parse_coordinate(text: &str) -> Result<Coordinate, NotationError> {
+    let invalid = || NotationError::InvalidCell(text.to_string());
+
+    match text.as_bytes() {
+        [col @ b'a'..=b'd', row @ b'1'..=b'4'] => {
+            Ok(Coordinate::new((row - b'1') as i8, (col - b'a') as i8))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// Writes `mv`. A placement names `piece_in_hand` when it is given.
+pub fn format_move(mv: Move, piece_in_hand: Option<Piece>) -> String {
+    match (mv, piece_in_hand) {
+        (Move::Give(piece), _) => format_piece(piece),
+        (Move::Place(coor), Some(piece)) => {
+            format!("{}:{}", format_coordinate(coor), format_piece(piece))
+        }
+        (Move::Place(coor), None) => format_coordinate(coor),
+    }
+}
+
+/// Reads a move. When `piece_in_hand` is given, a placement that names another
+/// piece is rejected.
+///
+/// Pieces are written in capitals and cells in lower case, so a word of
+/// capitals alone is read as a piece and anything else as a placement.
+pub fn parse_move(text: &str, piece_in_hand: Option<Piece>) -> Result<Move, NotationError> {
+    let invalid = || NotationError::InvalidMove(text.to_string());
+
+    if !text.is_empty() && text.chars().all(|letter| letter.is_ascii_uppercase()) {
+        return parse_piece(text).map(Move::Give);
+    }
+
+    let (cell, piece) = match text.split_once(':') {
+        Some((cell, piece)) => (cell, Some(parse_piece(piece).map_err(|_| invalid())?)),
+        None => (text, None),
+    };
+    let coor = parse_coordinate(cell).map_err(|_| invalid())?;
+
+    match (piece, piece_in_hand) {
+        (Some(found), Some(expected)) if found != expected => {
+            Err(NotationError::PieceMismatch { expected, found })
+        }
+        _ => Ok(Move::Place(coor)),
+    }
+}
+
+/// Writes a game record, naming the placed piece in every placement.
+pub fn format_moves(moves: &[Move]) -> String {
+    let mut piece_in_hand = None;
+
+    moves
+        .iter()
+        .map(|mv| {
+            let text = format_move(*mv, piece_in_hand);
+            piece_in_hand = match mv {
+                Move::Give(piece) => Some(*piece),
+                Move::Place(_) => None,
+            };
+            text
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Reads a game record. Only the notation is checked here: replay the moves with
+/// `Game::from_moves` to check them against the rules.
+pub fn parse_moves(text: &str) -> Result<Vec<Move>, NotationError> {
+    let mut piece_in_hand = None;
+
+    text.split_whitespace()
+        .map(|word| {
+            let mv = parse_move(word, piece_in_hand)?;
+            piece_in_hand = match mv {
+                Move::Give(piece) => Some(piece),
+                Move::Place(_) => None,
+            };
+            Ok(mv)
+        })
+        .collect()
+}
+
+fn format_board(board: &Board) -> String {
+    (0..BOARD_ROWS_NUM as i8)
+        .map(|row| {
+            let mut text = String::new();
+            let mut empty = 0;
+            for col in 0..BOARD_COLUMNS_NUM as i8 {
+                match board.get(Coordinate::new(row, col)) {
+                    Some(piece) => {
+                        if empty > 0 {
+                            text.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        text.push_str(&format_piece(piece));
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                text.push_str(&empty.to_string());
+            }
+            text
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn parse_board(text: &str) -> Result<Board, NotationError> {
+    let invalid = |reason: &str| NotationError::InvalidPosition(format!("{} in `{}`", reason, text));
+
+    let rows = text.split('/').collect::<Vec<_>>();
+    if rows.len() != BOARD_ROWS_NUM {
+        return Err(invalid("expected 4 rows"));
+    }
+
+    let mut board = Board::new();
+    for (row, row_text) in rows.iter().enumerate() {
+        let mut col = 0;
+        let mut rest = *row_text;
+        while !rest.is_empty() {
+            let first = rest.as_bytes()[0];
+            if first.is_ascii_digit() {
+                col += (first - b'0') as usize;
+                rest = &rest[1..];
+            } else {
+                let code = match rest.get(..4) {
+                    Some(code) if col < BOARD_COLUMNS_NUM => code,
+                    _ => return Err(invalid("row too long")),
+                };
+                let coor = Coordinate::new(row as i8, col as i8);
+                board.set(coor, Some(parse_piece(code)?));
+                col += 1;
+                rest = &rest[4..];
+            }
+        }
+        if col != BOARD_COLUMNS_NUM {
+            return Err(invalid("expected 4 cells per row"));
+        }
+    }
+
+    Ok(board)
+}
+
+/// Writes the placement and the give, separated by a space, e.g. `c3 DSSH`.
+impl fmt::Display for CompoundMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let moves = [
+            self.place.map(|coor| format_move(Move::Place(coor), None)),
+            self.give.map(|piece| format_move(Move::Give(piece), None)),
+        ];
+
+        write!(f, "{}", moves.iter().flatten().cloned().collect::<Vec<_>>().join(" "))
+    }
+}
+
+/// Reads a placement, a give or both, as written by `Display`.
+impl FromStr for CompoundMove {
+    type Err = NotationError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || NotationError::InvalidMove(text.to_string());
+
+        let mut mv = CompoundMove {
+            place: None,
+            give: None,
+        };
+        for word in text.split_whitespace() {
+            if mv.place.is_none() && mv.give.is_none() && word.len() == 2 {
+                mv.place = Some(parse_coordinate(word)?);
+            } else if mv.give.is_none() {
+                mv.give = Some(parse_piece(word)?);
+            } else {
+                return Err(invalid());
+            }
+        }
+
+        if mv.place.is_none() && mv.give.is_none() {
+            return Err(invalid());
+        }
+        Ok(mv)
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let available_pieces = if self.available_pieces.is_empty() {
+            "-".to_string()
+        } else {
+            self.available_pieces
+                .iter()
+                .map(|piece| format_piece(*piece))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        let (piece_in_hand, by) = match self.phase {
+            Phase::ChoosePieceForOpponent { by } => ("-".to_string(), by),
+            Phase::PlacePiece { by, piece } => (format_piece(piece), by),
+        };
+
+        let side = match by {
+            Turn::Player => 'P',
+            Turn::Opponent => 'O',
+        };
+
+        write!(
+            f,
+            "{} {} {} {}",
+            format_board(&self.board),
+            available_pieces,
+            piece_in_hand,
+            side
+        )
+    }
+}
+
+impl FromStr for Position {
+    type Err = NotationError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| NotationError::InvalidPosition(reason.to_string());
+
+        let fields = text.split_whitespace().collect::<Vec<_>>();
+        if fields.len() != 4 {
+            return Err(invalid("expected 4 fields"));
+        }
+
+        let board = parse_board(fields[0])?;
+
+        let available_pieces = match fields[1] {
+            "-" => vec![],
+            pieces => pieces
+                .split(',')
+                .map(parse_piece)
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+
+        let by = match fields[3] {
+            "P" => Turn::Player,
+            "O" => Turn::Opponent,
+            _ => return Err(invalid("side to move must be `P` or `O`")),
+        };
+
+        let phase = match fields[2] {
+            "-" => Phase::ChoosePieceForOpponent { by },
+            piece => Phase::PlacePiece {
+                by,
+                piece: parse_piece(piece)?,
+            },
+        };
+
+        // Every piece must be either on the board or available, exactly once
+        let mut seen = [false; 16];
+        let pieces = board
+            .iter()
+            .filter_map(|(_, cell)| cell)
+            .chain(available_pieces.iter().copied());
+        for piece in pieces {
+            if seen[piece.bits() as usize] {
+                return Err(invalid(&format!("{} appears twice", format_piece(piece))));
+            }
+            seen[piece.bits() as usize] = true;
+        }
+        if seen.iter().any(|each| !each) {
+            return Err(invalid("some pieces are neither on the board nor available"));
+        }
+
+        if let Phase::PlacePiece { piece, .. } = phase {
+            if !available_pieces.contains(&piece) {
+                return Err(invalid("the piece in hand is already on the board"));
+            }
+        }
+
+        Ok(Position {
+            board,
+            available_pieces,
+            phase,
+        })
+    }
+}
@@ -0,0 +1,215 @@
+use std::fmt;
+
+/// Asset names of the pieces, in the order they are laid out next to the board.
+pub const PIECE_NAMES: [&str; 16] = [
+    "Light_Round_Tall_Solid",
+    "Light_Round_Tall_Hollow",
+    "Light_Square_Tall_Solid",
+    "Light_Square_Tall_Hollow",
+    "Light_Square_Short_Solid",
+    "Light_Square_Short_Hollow",
+    "Light_Round_Short_Solid",
+    "Light_Round_Short_Hollow",
+    "Dark_Round_Short_Solid",
+    "Dark_Round_Short_Hollow",
+    "Dark_Square_Short_Solid",
+    "Dark_Square_Short_Hollow",
+    "Dark_Square_Tall_Solid",
+    "Dark_Square_Tall_Hollow",
+    "Dark_Round_Tall_Solid",
+    "Dark_Round_Tall_Hollow",
+];
+
+// Asset names indexed by `Piece::bits`.
+const NAMES_BY_BITS: [&str; 16] = [
+    "Light_Round_Short_Solid",
+    "Dark_Round_Short_Solid",
+    "Light_Square_Short_Solid",
+    "Dark_Square_Short_Solid",
+    "Light_Round_Tall_Solid",
+    "Dark_Round_Tall_Solid",
+    "Light_Square_Tall_Solid",
+    "Dark_Square_Tall_Solid",
+    "Light_Round_Short_Hollow",
+    "Dark_Round_Short_Hollow",
+    "Light_Square_Short_Hollow",
+    "Dark_Square_Short_Hollow",
+    "Light_Round_Tall_Hollow",
+    "Dark_Round_Tall_Hollow",
+    "Light_Square_Tall_Hollow",
+    "Dark_Square_Tall_Hollow",
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Color {
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Shape {
+    Round,
+    Square,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Height {
+    Short,
+    Tall,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Fill {
+    Solid,
+    Hollow,
+}
+
+/// One value of one of the four attributes, e.g. `Attribute::Color(Color::Dark)`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Attribute {
+    Color(Color),
+    Shape(Shape),
+    Height(Height),
+    Fill(Fill),
+}
+
+/// A Quarto piece, stored as a 4-bit mask with one bit per attribute.
+///
+/// A set bit means `Dark`, `Square`, `Tall` and `Hollow` respectively.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Piece(u8);
+
+impl Piece {
+    pub const COLOR: u8 = 0b0001;
+    pub const SHAPE: u8 = 0b0010;
+    pub const HEIGHT: u8 = 0b0100;
+    pub const FILL: u8 = 0b1000;
+    pub const ALL_ATTRIBUTES: u8 = 0b1111;
+
+    pub fn new(color: Color, shape: Shape, height: Height, fill: Fill) -> Self {
+        let mut bits = 0;
+        if color == Color::Dark {
+            bits |= Self::COLOR;
+        }
+        if shape == Shape::Square {
+            bits |= Self::SHAPE;
+        }
+        if height == Height::Tall {
+            bits |= Self::HEIGHT;
+        }
+        if fill == Fill::Hollow {
+            bits |= Self::FILL;
+        }
+        Self(bits)
+    }
+
+    /// Builds a piece from the low four bits of `bits`.
+    pub fn from_bits(bits: u8) -> Self {
+        Self(bits & Self::ALL_ATTRIBUTES)
+    }
+
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub fn color(self) -> Color {
+        if self.0 & Self::COLOR != 0 {
+            Color::Dark
+        } else {
+            Color::Light
+        }
+    }
+
+    pub fn shape(self) -> Shape {
+        if self.0 & Self::SHAPE != 0 {
+            Shape::Square
+        } else {
+            Shape::Round
+        }
+    }
+
+    pub fn height(self) -> Height {
+        if self.0 & Self::HEIGHT != 0 {
+            Height::Tall
+        } else {
+            Height::Short
+        }
+    }
+
+    pub fn fill(self) -> Fill {
+        if self.0 & Self::FILL != 0 {
+            Fill::Hollow
+        } else {
+            Fill::Solid
+        }
+    }
+
+    /// Value of this piece for the attribute selected by the single-bit `mask`.
+    pub fn attribute(self, mask: u8) -> Attribute {
+        match mask {
+            Self::COLOR => Attribute::Color(self.color()),
+            Self::SHAPE => Attribute::Shape(self.shape()),
+            Self::HEIGHT => Attribute::Height(self.height()),
+            _ => Attribute::Fill(self.fill()),
+        }
+    }
+
+    /// Values of this piece for every attribute set in `mask`.
+    pub fn attributes(self, mask: u8) -> Vec<Attribute> {
+        [Self::COLOR, Self::SHAPE, Self::HEIGHT, Self::FILL]
+            .into_iter()
+            .filter(|bit| mask & bit != 0)
+            .map(|bit| self.attribute(bit))
+            .collect()
+    }
+
+    /// Asset name of the piece, e.g. `Light_Round_Tall_Solid`.
+    pub fn name(self) -> &'static str {
+        NAMES_BY_BITS[self.0 as usize]
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        NAMES_BY_BITS
+            .iter()
+            .position(|each| *each == name)
+            .map(|bits| Self(bits as u8))
+    }
+
+    /// All sixteen pieces, in the same order as `PIECE_NAMES`.
+    pub fn all() -> Vec<Piece> {
+        PIECE_NAMES
+            .iter()
+            .filter_map(|name| Piece::from_name(name))
+            .collect()
+    }
+
+    /// Mask of the attributes that every piece in `pieces` has in common, whether
+    /// the shared value is the set or the clear one.
+    pub fn shared_attributes(pieces: &[Piece]) -> u8 {
+        let all_set = pieces
+            .iter()
+            .fold(Self::ALL_ATTRIBUTES, |acc, piece| acc & piece.0);
+        let all_clear = pieces
+            .iter()
+            .fold(Self::ALL_ATTRIBUTES, |acc, piece| acc & !piece.0);
+
+        (all_set | all_clear) & Self::ALL_ATTRIBUTES
+    }
+}
+
+impl fmt::Display for Piece {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl fmt::Display for Attribute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Attribute::Color(color) => write!(f, "{:?}", color),
+            Attribute::Shape(shape) => write!(f, "{:?}", shape),
+            Attribute::Height(height) => write!(f, "{:?}", height),
+            Attribute::Fill(fill) => write!(f, "{:?}", fill),
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! The two sides of a game. A `Player` chooses the moves of one side, whether
+//! they come from someone at the keyboard, a search engine or a peer over the
+//! network, and `Players` asks the side to move for its next move. The rules
+//! stay in `Game`: every move goes through `Game::apply`.
+
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+use crate::{
+    ai::AI,
+    board::Coordinate,
+    game::{Game, GameError, Move, Phase, Turn},
+    mcts::Mcts,
+    notation,
+    piece::Piece,
+    position::Position,
+    search::position_moves,
+};
+
+pub trait Player {
+    /// Chooses an empty cell to place the piece in hand on. `None` when the
+    /// choice is not made yet, e.g. a move not confirmed at the keyboard or not
+    /// received from the network: the player is asked again later.
+    fn choose_placement(&mut self, position: &Position) -> Option<Coordinate>;
+
+    /// Chooses the piece to hand over to the other side, or `None` when the
+    /// choice is not made yet.
+    fn choose_piece(&mut self, position: &Position) -> Option<Piece>;
+
+    /// Tells the player about a move of the other side, played from `position`.
+    fn observe(&mut self, _position: &Position, _mv: Move) {}
+
+    /// Gives up the move being chosen, after the position it was asked for
+    /// changed without it.
+    fn cancel(&mut self) {}
+
+    /// Whether a move is being chosen in the background.
+    fn is_thinking(&self) -> bool {
+        false
+    }
+
+    /// Whether the moves are chosen with the local keyboard.
+    fn is_local(&self) -> bool {
+        false
+    }
+
+    /// Whether moves can be taken back while this player is in the game.
+    fn allows_undo(&self) -> bool {
+        true
+    }
+}
+
+// Engines have nothing to choose once the board is full or no piece is left
+fn has_moves(position: &Position) -> bool {
+    !position_moves(position).is_empty()
+}
+
+impl Player for AI {
+    fn choose_placement(&mut self, position: &Position) -> Option<Coordinate> {
+        has_moves(position).then(|| self.select_place(position))
+    }
+
+    fn choose_piece(&mut self, position: &Position) -> Option<Piece> {
+        has_moves(position).then(|| self.select_piece(position))
+    }
+}
+
+impl Player for Mcts {
+    fn choose_placement(&mut self, position: &Position) -> Option<Coordinate> {
+        has_moves(position).then(|| self.select_place(position))
+    }
+
+    fn choose_piece(&mut self, position: &Position) -> Option<Piece> {
+        has_moves(position).then(|| self.select_piece(position))
+    }
+}
+
+/// Reads the lines of `reader` on a thread of their own, trimmed and leaving
+/// out blank ones.
+pub(crate) fn read_lines(reader: impl Read + Send + 'static) -> Receiver<String> {
+    let (sender, lines) = mpsc::channel();
+
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines() {
+            match line {
+                Ok(line) if line.trim().is_empty() => {}
+                Ok(line) => {
+                    if sender.send(line.trim().to_string()).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    log::warn!("{}", e);
+                    break;
+                }
+            }
+        }
+        log::info!("end of input");
+    });
+
+    lines
+}
+
+/// Player across the network. Each move is sent as one line in the `notation`
+/// format, and both ends must agree on which side each of them plays.
+pub struct RemotePlayer {
+    incoming: Receiver<String>,
+    outgoing: Box<dyn Write + Send>,
+}
+
+impl RemotePlayer {
+    /// Exchanges moves with the peer over `reader` and `writer`. Lines are read
+    /// on a thread of their own, so choosing a move never waits for the peer.
+    pub fn new(reader: impl Read + Send + 'static, writer: impl Write + Send + 'static) -> Self {
+        Self {
+            incoming: read_lines(reader),
+            outgoing: Box::new(writer),
+        }
+    }
+
+    /// Connects to a peer waiting with `listen`.
+    pub fn connect(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let stream = TcpStream::connect(address)?;
+        Ok(Self::new(stream.try_clone()?, stream))
+    }
+
+    /// Waits for one peer to `connect` on `address`.
+    pub fn listen(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let (stream, peer) = TcpListener::bind(address)?.accept()?;
+        log::info!("remote player connected from {}", peer);
+        Ok(Self::new(stream.try_clone()?, stream))
+    }
+
+    // Next move received from the peer, if any. Lines that are not a move of
+    // the phase of `position` are dropped.
+    fn receive(&mut self, position: &Position) -> Option<Move> {
+        while let Ok(line) = self.incoming.try_recv() {
+            match notation::parse_move(&line, position.piece_in_hand()) {
+                Ok(mv) if mv.phase_kind() == position.phase.kind() => return Some(mv),
+                Ok(_) => log::warn!("remote player: `{}` is not a move of this phase", line),
+                Err(e) => log::warn!("remote player: `{}`: {}", line, e),
+            }
+        }
+        None
+    }
+}
+
+impl Player for RemotePlayer {
+    fn choose_placement(&mut self, position: &Position) -> Option<Coordinate> {
+        match self.receive(position)? {
+            Move::Place(coor) => Some(coor),
+            Move::Give(_) => None,
+        }
+    }
+
+    fn choose_piece(&mut self, position: &Position) -> Option<Piece> {
+        match self.receive(position)? {
+            Move::Give(piece) => Some(piece),
+            Move::Place(_) => None,
+        }
+    }
+
+    fn observe(&mut self, position: &Position, mv: Move) {
+        let line = notation::format_move(mv, position.piece_in_hand());
+        if let Err(e) = writeln!(self.outgoing, "{}", line).and_then(|_| self.outgoing.flush()) {
+            log::warn!("remote player: could not send `{}`: {}", line, e);
+        }
+    }
+
+    fn allows_undo(&self) -> bool {
+        false
+    }
+}
+
+/// Players of the two sides of a game.
+pub struct Players {
+    pub player: Box<dyn Player>,
+    pub opponent: Box<dyn Player>,
+}
+
+impl Players {
+    pub fn new(player: Box<dyn Player>, opponent: Box<dyn Player>) -> Self {
+        Self { player, opponent }
+    }
+
+    pub fn get(&self, turn: Turn) -> &dyn Player {
+        match turn {
+            Turn::Player => self.player.as_ref(),
+            Turn::Opponent => self.opponent.as_ref(),
+        }
+    }
+
+    pub fn get_mut(&mut self, turn: Turn) -> &mut dyn Player {
+        match turn {
+            Turn::Player => self.player.as_mut(),
+            Turn::Opponent => self.opponent.as_mut(),
+        }
+    }
+
+    pub fn allows_undo(&self) -> bool {
+        self.player.allows_undo() && self.opponent.allows_undo()
+    }
+
+    pub fn cancel(&mut self) {
+        self.player.cancel();
+        self.opponent.cancel();
+    }
+
+    /// Asks the side to move for its move and plays it. Returns the move, or
+    /// `None` when the game is over or the player has not chosen yet.
+    pub fn step(&mut self, game: &mut Game) -> Result<Option<Move>, GameError> {
+        if game.is_over() {
+            return Ok(None);
+        }
+
+        let position = game.position();
+        let turn = game.turn();
+        let mv = match position.phase {
+            Phase::PlacePiece { .. } => self
+                .get_mut(turn)
+                .choose_placement(&position)
+                .map(Move::Place),
+            Phase::ChoosePieceForOpponent { .. } => {
+                self.get_mut(turn).choose_piece(&position).map(Move::Give)
+            }
+        };
+
+        match mv {
+            Some(mv) => {
+                game.apply(mv)?;
+                self.get_mut(turn.other()).observe(&position, mv);
+                Ok(Some(mv))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Plays moves until the game is over or a player has not chosen yet.
+    /// Returns the number of moves played.
+    pub fn play(&mut self, game: &mut Game) -> Result<usize, GameError> {
+        let mut played = 0;
+        while self.step(game)?.is_some() {
+            played += 1;
+        }
+        Ok(played)
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{
+    board::Board,
+    game::{Phase, Turn},
+    piece::Piece,
+};
+
+/// Rules state of a game without its history or AI: what a position string in
+/// the `notation` module describes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub board: Board,
+    /// Pieces not on the board yet, including the piece in hand.
+    pub available_pieces: Vec<Piece>,
+    pub phase: Phase,
+}
+
+impl Position {
+    pub fn initial() -> Self {
+        Self {
+            board: Board::new(),
+            available_pieces: Piece::all(),
+            phase: Phase::ChoosePieceForOpponent { by: Turn::Player },
+        }
+    }
+
+    /// Piece waiting to be placed, if any.
+    pub fn piece_in_hand(&self) -> Option<Piece> {
+        match self.phase {
+            Phase::PlacePiece { piece, .. } => Some(piece),
+            Phase::ChoosePieceForOpponent { .. } => None,
+        }
+    }
+
+    /// Side that placed the last piece, and so would have completed any Quarto on
+    /// the board.
+    pub fn last_placed_by(&self) -> Turn {
+        match self.phase {
+            Phase::ChoosePieceForOpponent { by } => by,
+            Phase::PlacePiece { by, .. } => by.other(),
+        }
+    }
+}
@@ -0,0 +1,535 @@
+//! Line protocol between a client and a Quarto engine, in the spirit of UCI, so
+//! that engines can run as processes of their own, written in any language.
+//!
+//! The client writes commands to the standard input of the engine, one per line:
+//!
+//! * `quarto`: first command; the engine answers `id name <name>`, then `quartook`.
+//! * `isready`: the engine answers `readyok` once it is done with earlier commands.
+//! * `newgame`: the next positions come from another game.
+//! * `position startpos [moves <record>]` or `position fen <position>`: sets the
+//!   position to think about, as a game record from the initial position or as a
+//!   position, both in the `notation` format.
+//! * `go place|give [movetime <ms>] [depth <n>]`: chooses the cell for the piece
+//!   in hand, or the piece to hand over. The engine answers any number of
+//!   `info <text>` lines, then `bestmove <move>`, or `bestmove none` when the
+//!   position has no such move.
+//! * `stop`: ends the thinking early. The engine still answers `bestmove`.
+//! * `quit`
+//!
+//! Lines either side does not understand are ignored. `serve` speaks the engine
+//! side for an `AI`, and `EnginePlayer` the client side for a `Player`.
+//!
+//! ```text
+//! > quarto
+//! < id name quarto-core AI Expert
+//! < quartook
+//! > position startpos moves LRTS
+//! > go place movetime 200
+//! < info depth 6 score 0 nodes 48211 time 200 pv b2 DSSH c1 LRTH
+//! < bestmove b2
+//! ```
+
+use std::{
+    error, fmt,
+    io::{self, BufRead, BufReader, Read, Write},
+    process::{Child, ChildStdin, Command as Process, Stdio},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::{self, Receiver},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    ai::{Reasoning, AI},
+    board::Coordinate,
+    endgame::Verdict,
+    game::{Game, GameError, Move, PhaseKind},
+    notation::{self, NotationError},
+    piece::Piece,
+    player::{self, Player},
+    position::Position,
+    search::{position_moves, CompoundMove},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProtocolError {
+    UnknownCommand(String),
+    Malformed(String),
+    Notation(NotationError),
+    /// The moves of a `position startpos` command break the rules.
+    Game(GameError),
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtocolError::UnknownCommand(line) => write!(f, "unknown command `{}`", line),
+            ProtocolError::Malformed(line) => write!(f, "malformed command `{}`", line),
+            ProtocolError::Notation(error) => write!(f, "{}", error),
+            ProtocolError::Game(error) => write!(f, "illegal move in position: {}", error),
+        }
+    }
+}
+
+impl error::Error for ProtocolError {}
+
+impl From<NotationError> for ProtocolError {
+    fn from(error: NotationError) -> Self {
+        ProtocolError::Notation(error)
+    }
+}
+
+impl From<GameError> for ProtocolError {
+    fn from(error: GameError) -> Self {
+        ProtocolError::Game(error)
+    }
+}
+
+/// Asks the engine for a move of the phase `kind`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Go {
+    pub kind: PhaseKind,
+    /// Thinking time, instead of the engine's own.
+    pub movetime: Option<Duration>,
+    /// Deepest search, instead of the engine's own.
+    pub depth: Option<u32>,
+}
+
+/// Line from the client to the engine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Quarto,
+    IsReady,
+    NewGame,
+    Position(Position),
+    Go(Go),
+    Stop,
+    Quit,
+}
+
+impl Command {
+    pub fn parse(line: &str) -> Result<Self, ProtocolError> {
+        let malformed = || ProtocolError::Malformed(line.to_string());
+        let (name, rest) = match line.trim().split_once(' ') {
+            Some((name, rest)) => (name, rest.trim()),
+            None => (line.trim(), ""),
+        };
+
+        match name {
+            "quarto" => Ok(Command::Quarto),
+            "isready" => Ok(Command::IsReady),
+            "newgame" => Ok(Command::NewGame),
+            "stop" => Ok(Command::Stop),
+            "quit" => Ok(Command::Quit),
+            "position" => {
+                if let Some(position) = rest.strip_prefix("fen ") {
+                    return Ok(Command::Position(position.trim().parse()?));
+                }
+                let moves = match rest.strip_prefix("startpos") {
+                    Some(moves) => match moves.trim() {
+                        "" => "",
+                        moves => moves.strip_prefix("moves").ok_or_else(malformed)?,
+                    },
+                    None => return Err(malformed()),
+                };
+                let game = Game::from_moves(1, &notation::parse_moves(moves)?)?;
+                Ok(Command::Position(game.position()))
+            }
+            "go" => {
+                let mut words = rest.split_whitespace();
+                let kind = match words.next() {
+                    Some("place") => PhaseKind::PlacePiece,
+                    Some("give") => PhaseKind::ChoosePieceForOpponent,
+                    _ => return Err(malformed()),
+                };
+
+                let mut go = Go {
+                    kind,
+                    movetime: None,
+                    depth: None,
+                };
+                while let Some(option) = words.next() {
+                    let value = words.next().ok_or_else(malformed)?;
+                    match option {
+                        "movetime" => {
+                            let ms = value.parse().map_err(|_| malformed())?;
+                            go.movetime = Some(Duration::from_millis(ms));
+                        }
+                        "depth" => go.depth = Some(value.parse().map_err(|_| malformed())?),
+                        _ => return Err(malformed()),
+                    }
+                }
+                Ok(Command::Go(go))
+            }
+            _ => Err(ProtocolError::UnknownCommand(line.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Command::Quarto => write!(f, "quarto"),
+            Command::IsReady => write!(f, "isready"),
+            Command::NewGame => write!(f, "newgame"),
+            Command::Position(position) => write!(f, "position fen {}", position),
+            Command::Go(go) => {
+                let kind = match go.kind {
+                    PhaseKind::PlacePiece => "place",
+                    PhaseKind::ChoosePieceForOpponent => "give",
+                };
+                write!(f, "go {}", kind)?;
+                if let Some(movetime) = go.movetime {
+                    write!(f, " movetime {}", movetime.as_millis())?;
+                }
+                if let Some(depth) = go.depth {
+                    write!(f, " depth {}", depth)?;
+                }
+                Ok(())
+            }
+            Command::Stop => write!(f, "stop"),
+            Command::Quit => write!(f, "quit"),
+        }
+    }
+}
+
+/// Line from the engine to the client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reply {
+    Id(String),
+    QuartoOk,
+    ReadyOk,
+    /// Free text about the thinking, shown to people rather than read by the
+    /// client.
+    Info(String),
+    BestMove(Option<Move>),
+}
+
+impl Reply {
+    pub fn parse(line: &str) -> Result<Self, ProtocolError> {
+        let (name, rest) = match line.trim().split_once(' ') {
+            Some((name, rest)) => (name, rest.trim()),
+            None => (line.trim(), ""),
+        };
+
+        match name {
+            "id" => match rest.strip_prefix("name ") {
+                Some(name) => Ok(Reply::Id(name.trim().to_string())),
+                None => Err(ProtocolError::Malformed(line.to_string())),
+            },
+            "quartook" => Ok(Reply::QuartoOk),
+            "readyok" => Ok(Reply::ReadyOk),
+            "info" => Ok(Reply::Info(rest.to_string())),
+            "bestmove" => match rest {
+                "none" => Ok(Reply::BestMove(None)),
+                mv => Ok(Reply::BestMove(Some(notation::parse_move(mv, None)?))),
+            },
+            _ => Err(ProtocolError::UnknownCommand(line.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Reply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reply::Id(name) => write!(f, "id name {}", name),
+            Reply::QuartoOk => write!(f, "quartook"),
+            Reply::ReadyOk => write!(f, "readyok"),
+            Reply::Info(text) => write!(f, "info {}", text),
+            Reply::BestMove(Some(mv)) => write!(f, "bestmove {}", notation::format_move(*mv, None)),
+            Reply::BestMove(None) => write!(f, "bestmove none"),
+        }
+    }
+}
+
+fn format_pv(pv: &[CompoundMove]) -> String {
+    pv.iter()
+        .map(|mv| mv.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Readout of how `ai` came to its last move, thinking for `time`
+fn info(ai: &AI, time: Duration) -> Option<String> {
+    let text = match ai.last_reasoning()? {
+        Reasoning::Book => "string book move".to_string(),
+        Reasoning::Blunder => "string blunder".to_string(),
+        Reasoning::Noisy { depth, score } => {
+            format!("depth {} score {} string noisy scores", depth, score)
+        }
+        Reasoning::Endgame(solution) => {
+            let score = match solution.verdict {
+                Verdict::Win { moves } => format!("win {}", moves),
+                Verdict::Loss { moves } => format!("loss {}", moves),
+                Verdict::Draw => "draw".to_string(),
+            };
+            format!(
+                "score {} nodes {} time {} pv {}",
+                score,
+                solution.nodes,
+                time.as_millis(),
+                format_pv(&solution.pv)
+            )
+        }
+        Reasoning::Search(result) => format!(
+            "depth {} score {} nodes {} time {} pv {}",
+            result.depth,
+            result.score,
+            result.nodes,
+            time.as_millis(),
+            format_pv(&result.pv)
+        ),
+    };
+    Some(text)
+}
+
+// Plays `go` with `ai` in `position`, with the limits it sets
+fn go(ai: &mut AI, position: &Position, go: Go) -> Option<Move> {
+    if position.phase.kind() != go.kind || position_moves(position).is_empty() {
+        return None;
+    }
+
+    let (depth, time_budget) = (ai.depth, ai.time_budget);
+    ai.depth = go.depth.unwrap_or(depth);
+    ai.time_budget = go.movetime.unwrap_or(time_budget);
+
+    let mv = match go.kind {
+        PhaseKind::PlacePiece => Move::Place(ai.select_place(position)),
+        PhaseKind::ChoosePieceForOpponent => Move::Give(ai.select_piece(position)),
+    };
+
+    ai.depth = depth;
+    ai.time_budget = time_budget;
+    Some(mv)
+}
+
+fn send(output: &mut impl Write, reply: Reply) -> io::Result<()> {
+    writeln!(output, "{}", reply)?;
+    output.flush()
+}
+
+/// Answers the commands read from `input` with `ai`, on `output`, until `quit`
+/// or the end of `input`. `stop` and `quit` are read while thinking: `stop`
+/// makes the AI answer with the best move found so far, and `quit` ends the
+/// thinking and every command still waiting.
+pub fn serve(
+    mut ai: AI,
+    name: &str,
+    input: impl Read + Send + 'static,
+    mut output: impl Write,
+) -> io::Result<()> {
+    let stop = Arc::new(AtomicBool::new(false));
+    ai.set_stop_flag(stop.clone());
+    // `go` commands are numbered from 1 as they are read; those up to this one
+    // are stopped
+    let stopped = Arc::new(AtomicU64::new(0));
+
+    let (sender, commands) = mpsc::channel();
+    let reader_stop = stop.clone();
+    let reader_stopped = stopped.clone();
+    thread::spawn(move || {
+        let mut gos = 0;
+        for line in BufReader::new(input).lines().map_while(Result::ok) {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let command = match Command::parse(&line) {
+                Ok(command) => command,
+                Err(e) => {
+                    log::warn!("engine: {}", e);
+                    continue;
+                }
+            };
+            match command {
+                Command::Go(_) => gos += 1,
+                Command::Stop => reader_stopped.store(gos, Ordering::SeqCst),
+                Command::Quit => reader_stopped.store(u64::MAX, Ordering::SeqCst),
+                _ => {}
+            }
+            if matches!(command, Command::Stop | Command::Quit) {
+                reader_stop.store(true, Ordering::SeqCst);
+            }
+
+            if command != Command::Stop && sender.send(command).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut position = Position::initial();
+    let mut gos = 0;
+    while let Ok(command) = commands.recv() {
+        if stopped.load(Ordering::SeqCst) == u64::MAX {
+            break;
+        }
+
+        match command {
+            Command::Quarto => {
+                send(&mut output, Reply::Id(name.to_string()))?;
+                send(&mut output, Reply::QuartoOk)?;
+            }
+            Command::IsReady => send(&mut output, Reply::ReadyOk)?,
+            Command::NewGame => position = Position::initial(),
+            Command::Position(new_position) => position = new_position,
+            Command::Go(limits) => {
+                gos += 1;
+                // Cleared first, so that a stop read meanwhile is not lost
+                stop.store(false, Ordering::SeqCst);
+                if stopped.load(Ordering::SeqCst) >= gos {
+                    stop.store(true, Ordering::SeqCst);
+                }
+
+                let started = Instant::now();
+                let mv = go(&mut ai, &position, limits);
+                if let Some(text) = mv.and_then(|_| info(&ai, started.elapsed())) {
+                    send(&mut output, Reply::Info(text))?;
+                }
+                send(&mut output, Reply::BestMove(mv))?;
+            }
+            Command::Stop => {}
+            Command::Quit => break,
+        }
+    }
+
+    Ok(())
+}
+
+/// Time an engine has to quit once asked to, before it is killed.
+const ENGINE_QUIT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Player whose moves come from an engine process speaking this protocol.
+/// Asking for a move sends the position and returns `None` until the engine
+/// answers.
+pub struct EnginePlayer {
+    process: Child,
+    stdin: ChildStdin,
+    replies: Receiver<String>,
+    /// Position the engine is thinking about.
+    pending: Option<Position>,
+    /// Stopped searches, whose `bestmove` is still to come and to be dropped.
+    stopped: usize,
+}
+
+impl EnginePlayer {
+    /// Starts the engine `program` with `args`.
+    pub fn spawn(program: &str, args: &[&str]) -> io::Result<Self> {
+        let mut process = Process::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = process.stdin.take().expect("stdin is piped");
+        let stdout = process.stdout.take().expect("stdout is piped");
+
+        let mut player = Self {
+            process,
+            stdin,
+            replies: player::read_lines(stdout),
+            pending: None,
+            stopped: 0,
+        };
+        player.send(Command::Quarto);
+        player.send(Command::NewGame);
+        Ok(player)
+    }
+
+    fn send(&mut self, command: Command) {
+        if let Err(e) = writeln!(self.stdin, "{}", command).and_then(|_| self.stdin.flush()) {
+            log::warn!("engine: {}", e);
+        }
+    }
+
+    // Move chosen for `position`, once the engine has answered. Starts the
+    // thinking when `position` is not the one being thought about.
+    fn think(&mut self, position: &Position) -> Option<Move> {
+        while let Ok(line) = self.replies.try_recv() {
+            match Reply::parse(&line) {
+                Ok(Reply::BestMove(_)) if self.stopped > 0 => self.stopped -= 1,
+                // The search is over even when its position is no longer the
+                // one asked about, so there is nothing left to stop
+                Ok(Reply::BestMove(mv)) => {
+                    if self.pending.take().as_ref() == Some(position) {
+                        if mv.is_none() {
+                            log::warn!("engine: no move in `{}`", position);
+                        }
+                        return mv;
+                    }
+                }
+                Ok(Reply::Info(text)) => log::info!("engine: {}", text),
+                Ok(Reply::Id(name)) => log::info!("engine: {}", name),
+                Ok(_) => {}
+                Err(e) => log::warn!("engine: {}", e),
+            }
+        }
+
+        if self.pending.as_ref() != Some(position) {
+            self.cancel();
+            self.send(Command::Position(position.clone()));
+            self.send(Command::Go(Go {
+                kind: position.phase.kind(),
+                movetime: None,
+                depth: None,
+            }));
+            self.pending = Some(position.clone());
+        }
+
+        None
+    }
+}
+
+impl Player for EnginePlayer {
+    fn choose_placement(&mut self, position: &Position) -> Option<Coordinate> {
+        match self.think(position)? {
+            Move::Place(coor) => Some(coor),
+            Move::Give(_) => None,
+        }
+    }
+
+    fn choose_piece(&mut self, position: &Position) -> Option<Piece> {
+        match self.think(position)? {
+            Move::Give(piece) => Some(piece),
+            Move::Place(_) => None,
+        }
+    }
+
+    fn cancel(&mut self) {
+        if self.pending.take().is_some() {
+            self.send(Command::Stop);
+            self.stopped += 1;
+        }
+    }
+
+    fn is_thinking(&self) -> bool {
+        self.pending.is_some()
+    }
+}
+
+impl Drop for EnginePlayer {
+    fn drop(&mut self) {
+        self.send(Command::Quit);
+
+        let deadline = Instant::now() + ENGINE_QUIT_TIMEOUT;
+        loop {
+            match self.process.try_wait() {
+                Ok(Some(_)) => return,
+                Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(10)),
+                Ok(None) => {
+                    log::warn!("engine: did not quit in time, killing it");
+                    break;
+                }
+                Err(e) => {
+                    log::warn!("engine: {}", e);
+                    break;
+                }
+            }
+        }
+
+        if let Err(e) = self.process.kill().and_then(|_| self.process.wait()) {
+            log::warn!("engine: {}", e);
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//! Replays of finished or saved games, stepped by hand or auto-played.
+
+use std::time::Duration;
+
+use crate::{
+    board::Coordinate,
+    game::{Game, GameError, GameOutcome, Move, Phase},
+    notation,
+    piece::Piece,
+};
+
+/// Delay between two moves while auto-playing, from slowest to fastest.
+pub const AUTO_PLAY_INTERVALS: [Duration; 5] = [
+    Duration::from_millis(2000),
+    Duration::from_millis(1000),
+    Duration::from_millis(500),
+    Duration::from_millis(250),
+    Duration::from_millis(100),
+];
+
+/// Steps through the moves of a game. The position shown is `game`, whose undo
+/// and redo stacks hold the moves before and after it.
+pub struct Replay {
+    pub game: Game,
+    pub auto_play: bool,
+    speed: usize,
+    since_last_step: Duration,
+}
+
+impl Replay {
+    /// Starts a replay of the moves played in `game`, rewound to the first move.
+    pub fn new(game: &Game) -> Result<Self, GameError> {
+        let mut game =
+            Game::from_position_and_moves(game.level, game.start().clone(), game.history())?;
+        while game.undo().is_some() {}
+
+        Ok(Self {
+            game,
+            auto_play: false,
+            speed: 1,
+            since_last_step: Duration::ZERO,
+        })
+    }
+
+    pub fn step_forward(&mut self) -> bool {
+        self.since_last_step = Duration::ZERO;
+        self.game.redo().is_some()
+    }
+
+    pub fn step_back(&mut self) -> bool {
+        self.since_last_step = Duration::ZERO;
+        self.game.undo().is_some()
+    }
+
+    pub fn rewind(&mut self) -> bool {
+        let mut stepped = false;
+        while self.step_back() {
+            stepped = true;
+        }
+        stepped
+    }
+
+    pub fn fast_forward(&mut self) -> bool {
+        let mut stepped = false;
+        while self.step_forward() {
+            stepped = true;
+        }
+        stepped
+    }
+
+    pub fn toggle_auto_play(&mut self) {
+        self.auto_play = !self.auto_play;
+        self.since_last_step = Duration::ZERO;
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (self.speed + 1).min(AUTO_PLAY_INTERVALS.len() - 1);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = self.speed.saturating_sub(1);
+    }
+
+    /// Delay between two moves while auto-playing.
+    pub fn interval(&self) -> Duration {
+        AUTO_PLAY_INTERVALS[self.speed]
+    }
+
+    /// Advances the auto-play clock by `dt`. Returns `true` when a move was played.
+    pub fn update(&mut self, dt: Duration) -> bool {
+        if !self.auto_play {
+            return false;
+        }
+
+        self.since_last_step += dt;
+        if self.since_last_step < self.interval() {
+            return false;
+        }
+
+        let stepped = self.step_forward();
+        if !stepped {
+            self.auto_play = false;
+        }
+        stepped
+    }
+
+    pub fn move_number(&self) -> usize {
+        self.game.history().len()
+    }
+
+    pub fn moves_num(&self) -> usize {
+        self.game.history().len() + self.game.redo_moves().len()
+    }
+
+    /// Piece and cell to highlight: the piece in hand, or the piece about to be
+    /// handed over, and the cell of the last placement.
+    pub fn highlight(&self) -> (Piece, Option<Coordinate>) {
+        let coor = self.game.history().iter().rev().find_map(|mv| match mv {
+            Move::Place(coor) => Some(*coor),
+            Move::Give(_) => None,
+        });
+
+        let next_given = match self.game.redo_moves().first() {
+            Some(Move::Give(piece)) => Some(*piece),
+            _ => None,
+        };
+
+        let piece = self
+            .game
+            .piece_in_hand()
+            .or(next_given)
+            .or_else(|| self.game.available_pieces.first().copied())
+            .unwrap_or_else(|| Piece::all()[0]);
+
+        (piece, coor)
+    }
+
+    /// Move number, last move and what happens next, e.g.
+    /// `Replay 2/12 (c3:LRTS): Opponent to choose a piece`.
+    pub fn describe(&self) -> String {
+        let last_move = match self.game.history().last() {
+            Some(Move::Place(coor)) => {
+                notation::format_move(Move::Place(*coor), self.game.board.get(*coor))
+            }
+            Some(mv) => notation::format_move(*mv, None),
+            None => "start".to_string(),
+        };
+
+        let next = match (&self.game.outcome, self.game.phase) {
+            (GameOutcome::Won { by, .. }, _) => format!("{:?} won", by),
+            (GameOutcome::Draw, _) => "Draw".to_string(),
+            (GameOutcome::InProgress, Phase::ChoosePieceForOpponent { by }) => {
+                format!("{:?} to choose a piece", by)
+            }
+            (GameOutcome::InProgress, Phase::PlacePiece { by, piece }) => {
+                format!("{:?} to place {}", by, notation::format_piece(piece))
+            }
+        };
+
+        let auto_play = if self.auto_play {
+            format!(" [auto-play {} ms]", self.interval().as_millis())
+        } else {
+            String::new()
+        };
+
+        format!(
+            "Replay {}/{} ({}): {}{}",
+            self.move_number(),
+            self.moves_num(),
+            last_move,
+            next,
+            auto_play
+        )
+    }
+}
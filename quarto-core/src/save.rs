@@ -0,0 +1,165 @@
+//! Save files for games in progress or finished.
+//!
+//! A save file is plain text, one `key value` pair per line, starting with the
+//! format version:
+//!
+//! ```text
+//! quarto-save 2
+//! level 1
+//! start 4/4/4/4 LRTS,LRTH,... - P
+//! moves LRTS c3:LRTS DSSH a1:DSSH
+//! undone 1
+//! position 4/4/2LRTS1/4 LRTH,... DSSH P
+//! ```
+//!
+//! `start` is the position the game started from, and `moves` the game record
+//! from there, both in the `notation` format. The record includes the moves that
+//! were taken back with undo; `undone` counts those moves, from the end of the
+//! record. `position` is written for readers and checked on load. Version 1
+//! files have no `start` line: their games start from the initial position.
+
+use std::{error, fmt, fs, io, path::Path};
+
+use crate::{
+    game::{Game, GameError},
+    notation::{self, NotationError},
+    position::Position,
+};
+
+pub const SAVE_FORMAT_VERSION: u32 = 2;
+
+const SAVE_FORMAT_TAG: &str = "quarto-save";
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    UnsupportedVersion(u32),
+    Malformed(String),
+    Notation(NotationError),
+    /// The recorded moves break the rules.
+    Game(GameError),
+    /// The recorded moves do not lead to the recorded position.
+    PositionMismatch { recorded: String, replayed: String },
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(error) => write!(f, "{}", error),
+            SaveError::UnsupportedVersion(version) => write!(
+                f,
+                "save format version {} is not supported (expected {})",
+                version, SAVE_FORMAT_VERSION
+            ),
+            SaveError::Malformed(reason) => write!(f, "malformed save file: {}", reason),
+            SaveError::Notation(error) => write!(f, "{}", error),
+            SaveError::Game(error) => write!(f, "illegal move in save file: {}", error),
+            SaveError::PositionMismatch { recorded, replayed } => write!(
+                f,
+                "moves lead to `{}` but the save file records `{}`",
+                replayed, recorded
+            ),
+        }
+    }
+}
+
+impl error::Error for SaveError {}
+
+impl From<io::Error> for SaveError {
+    fn from(error: io::Error) -> Self {
+        SaveError::Io(error)
+    }
+}
+
+impl From<NotationError> for SaveError {
+    fn from(error: NotationError) -> Self {
+        SaveError::Notation(error)
+    }
+}
+
+impl From<GameError> for SaveError {
+    fn from(error: GameError) -> Self {
+        SaveError::Game(error)
+    }
+}
+
+pub fn write_game(game: &Game) -> String {
+    let redo_moves = game.redo_moves();
+    let moves = [game.history(), &redo_moves].concat();
+
+    format!(
+        "{} {}\nlevel {}\nstart {}\nmoves {}\nundone {}\nposition {}\n",
+        SAVE_FORMAT_TAG,
+        SAVE_FORMAT_VERSION,
+        game.level,
+        game.start(),
+        notation::format_moves(&moves),
+        redo_moves.len(),
+        game.position()
+    )
+}
+
+pub fn read_game(text: &str) -> Result<Game, SaveError> {
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+
+    let mut field = |key: &str| -> Result<String, SaveError> {
+        let line = lines
+            .next()
+            .ok_or_else(|| SaveError::Malformed(format!("missing `{}` line", key)))?;
+        match line.split_once(' ') {
+            Some((found, value)) if found == key => Ok(value.trim().to_string()),
+            _ if line.trim() == key => Ok(String::new()),
+            _ => Err(SaveError::Malformed(format!(
+                "expected `{}` line, found `{}`",
+                key, line
+            ))),
+        }
+    };
+
+    let version = field(SAVE_FORMAT_TAG)?
+        .parse::<u32>()
+        .map_err(|_| SaveError::Malformed("version is not a number".to_string()))?;
+    if !(1..=SAVE_FORMAT_VERSION).contains(&version) {
+        return Err(SaveError::UnsupportedVersion(version));
+    }
+
+    let level = field("level")?
+        .parse::<usize>()
+        .map_err(|_| SaveError::Malformed("level is not a number".to_string()))?;
+    let start = match version {
+        1 => Position::initial(),
+        _ => field("start")?.parse::<Position>()?,
+    };
+    let moves = notation::parse_moves(&field("moves")?)?;
+    let undone = field("undone")?
+        .parse::<usize>()
+        .map_err(|_| SaveError::Malformed("undone is not a number".to_string()))?;
+    let recorded = field("position")?;
+
+    if undone > moves.len() {
+        return Err(SaveError::Malformed(
+            "more undone moves than recorded moves".to_string(),
+        ));
+    }
+
+    let mut game = Game::from_position_and_moves(level, start, &moves)?;
+    for _ in 0..undone {
+        game.undo();
+    }
+
+    let replayed = game.position().to_string();
+    if replayed != recorded {
+        return Err(SaveError::PositionMismatch { recorded, replayed });
+    }
+
+    Ok(game)
+}
+
+pub fn save_game(game: &Game, path: impl AsRef<Path>) -> Result<(), SaveError> {
+    fs::write(path, write_game(game))?;
+    Ok(())
+}
+
+pub fn load_game(path: impl AsRef<Path>) -> Result<Game, SaveError> {
+    read_game(&fs::read_to_string(path)?)
+}
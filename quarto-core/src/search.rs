@@ -0,0 +1,698 @@
+//! Negamax search with alpha-beta pruning.
+//!
+//! A turn is searched as one compound move: placing the piece in hand, then
+//! choosing the piece the other side has to place. Scores are from the point of
+//! view of the side to move: a win is worth `WIN_SCORE` minus the number of
+//! compound moves needed to reach it, so quicker wins score higher. Positions
+//! at the depth limit are scored by `evaluation`.
+//!
+//! With `Search::set_threads`, the root moves are split over several threads
+//! sharing the transposition table.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicI32, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use rayon::prelude::*;
+
+use crate::{
+    board::{Board, Coordinate},
+    evaluation,
+    game::Phase,
+    piece::Piece,
+    position::Position,
+    symmetry,
+    transposition::{Bound, TranspositionTable, DEFAULT_TABLE_SIZE_MB},
+};
+
+pub const WIN_SCORE: i32 = 1000;
+
+const INFINITY: i32 = WIN_SCORE + 1;
+
+// Shallower nodes are cheaper to search again than to hash
+const MIN_TT_DEPTH: u32 = 2;
+
+/// One turn: where the piece in hand goes and which piece is handed over.
+///
+/// `place` is `None` when the search starts in the `ChoosePieceForOpponent`
+/// phase, and `give` is `None` when placing ends the game. Both are `None` when
+/// there is no move to play.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CompoundMove {
+    pub place: Option<Coordinate>,
+    pub give: Option<Piece>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    pub best: CompoundMove,
+    pub score: i32,
+    pub depth: u32,
+    pub nodes: u64,
+    /// Line of play expected from both sides, starting with `best`.
+    pub pv: Vec<CompoundMove>,
+}
+
+/// Set of pieces as a 16-bit mask indexed by `Piece::bits`.
+pub(crate) fn piece_mask(pieces: impl Iterator<Item = Piece>) -> u16 {
+    pieces.fold(0, |mask, piece| mask | 1 << piece.bits())
+}
+
+pub(crate) fn pieces_in(mask: u16) -> impl Iterator<Item = Piece> {
+    (0..16u8)
+        .filter(move |bits| mask & 1 << bits != 0)
+        .map(Piece::from_bits)
+}
+
+/// Every compound move for the side holding `piece_in_hand`, or choosing a
+/// piece when there is none, with `available` pieces left to hand over.
+pub(crate) fn compound_moves(
+    board: &Board,
+    piece_in_hand: Option<Piece>,
+    available: u16,
+) -> Vec<CompoundMove> {
+    let piece = match piece_in_hand {
+        Some(piece) => piece,
+        None => {
+            return pieces_in(available)
+                .map(|give| CompoundMove {
+                    place: None,
+                    give: Some(give),
+                })
+                .collect()
+        }
+    };
+
+    let mut moves = vec![];
+    for coor in board.free_coords() {
+        if board.is_winning_placement(coor, piece) || available == 0 {
+            moves.push(CompoundMove {
+                place: Some(coor),
+                give: None,
+            });
+        } else {
+            moves.extend(pieces_in(available).map(|give| CompoundMove {
+                place: Some(coor),
+                give: Some(give),
+            }));
+        }
+    }
+    moves
+}
+
+/// Every compound move for the side to move in `position`.
+pub(crate) fn position_moves(position: &Position) -> Vec<CompoundMove> {
+    let piece_in_hand = position.piece_in_hand();
+    let available = piece_mask(position.available_pieces.iter().copied())
+        & !piece_in_hand.map_or(0, |piece| 1 << piece.bits());
+
+    compound_moves(&position.board, piece_in_hand, available)
+}
+
+/// State inside the search: a board, the piece the side to move has to place
+/// and the pieces left to hand over afterwards.
+#[derive(Debug, Copy, Clone)]
+struct Node {
+    board: Board,
+    piece: Piece,
+    available: u16,
+}
+
+impl Node {
+    /// Places the piece in hand on `coor` and hands over `give`.
+    fn play(&self, coor: Coordinate, give: Piece) -> Node {
+        let mut board = self.board;
+        board.set(coor, Some(self.piece));
+        Node {
+            board,
+            piece: give,
+            available: self.available & !(1 << give.bits()),
+        }
+    }
+}
+
+/// Whether `score` is a proven win or loss rather than a heuristic guess.
+pub fn is_decisive(score: i32) -> bool {
+    score.abs() > WIN_SCORE - 100
+}
+
+// Wins are stored counting plies from the stored node rather than from the
+// root, so they stay valid when the node is reached at another ply
+fn score_to_tt(score: i32, ply: u32) -> i32 {
+    match score {
+        _ if !is_decisive(score) => score,
+        _ if score > 0 => score + ply as i32,
+        _ => score - ply as i32,
+    }
+}
+
+fn score_from_tt(score: i32, ply: u32) -> i32 {
+    match score {
+        _ if !is_decisive(score) => score,
+        _ if score > 0 => score - ply as i32,
+        _ => score + ply as i32,
+    }
+}
+
+// Longest line of play: one compound move per piece, plus a give to start
+const MAX_PLY: usize = 18;
+
+// Nodes searched between two looks at the clock
+const NODES_PER_CLOCK_CHECK: u64 = 1024;
+
+#[derive(Debug, Clone)]
+pub struct Search {
+    nodes: u64,
+    /// Counted by each search rather than by the shared table, whose threads
+    /// would otherwise all write to one counter.
+    tt_hits: u64,
+    rng: Option<oorandom::Rand32>,
+    /// Kept between runs, so later moves of a game reuse earlier results.
+    /// Clones of the search share it.
+    pub tt: Arc<TranspositionTable>,
+    /// Threads the root moves are split over; see `set_threads`.
+    threads: usize,
+    /// Pool of `threads` threads, when that is not the size of the global pool.
+    pool: Option<Arc<rayon::ThreadPool>>,
+    deadline: Option<Instant>,
+    /// Set, usually from another thread, to end the searches early.
+    stop: Option<Arc<AtomicBool>>,
+    aborted: bool,
+    // Principal variation found below each ply, from that ply on
+    pv: [[Option<CompoundMove>; MAX_PLY]; MAX_PLY],
+    pv_len: [usize; MAX_PLY],
+}
+
+impl Default for Search {
+    fn default() -> Self {
+        Self::new(DEFAULT_TABLE_SIZE_MB)
+    }
+}
+
+impl Search {
+    /// Search with a transposition table of `table_size_mb` megabytes.
+    pub fn new(table_size_mb: usize) -> Self {
+        Self {
+            nodes: 0,
+            tt_hits: 0,
+            rng: None,
+            tt: Arc::new(TranspositionTable::new(table_size_mb)),
+            threads: 1,
+            pool: None,
+            deadline: None,
+            stop: None,
+            aborted: false,
+            pv: [[None; MAX_PLY]; MAX_PLY],
+            pv_len: [0; MAX_PLY],
+        }
+    }
+
+    /// Searches root moves in an order shuffled by `seed`, so that equally good
+    /// moves are picked at random but reproducibly.
+    pub fn with_seed(seed: u64, table_size_mb: usize) -> Self {
+        Self {
+            rng: Some(oorandom::Rand32::new(seed)),
+            ..Self::new(table_size_mb)
+        }
+    }
+
+    /// Finds the best compound move for the side to move in `position`, looking
+    /// `depth` compound moves ahead.
+    pub fn run(&mut self, position: &Position, depth: u32) -> SearchResult {
+        self.start(None);
+
+        let candidates = self.shuffled_root_moves(position);
+        match self.search_root(position, &candidates, depth) {
+            Some(result) => result,
+            None => self.stopped(&candidates),
+        }
+    }
+
+    /// Scores every compound move of `position` on its own, rather than only
+    /// as well as needed to tell that it is not the best.
+    pub fn score_moves(&mut self, position: &Position, depth: u32) -> Vec<(CompoundMove, i32)> {
+        self.start(None);
+
+        position_moves(position)
+            .into_iter()
+            .map(|mv| (mv, self.score_root_move(position, mv, depth, -INFINITY)))
+            .collect()
+    }
+
+    /// Scores every compound move of `position` as `score_moves` does, one
+    /// depth deeper at a time, up to `max_depth`, until `budget` runs out.
+    /// Returns the scores of the deepest depth that completed, with that depth;
+    /// as in `run_for`, the first depth always completes unless the stop flag
+    /// is set.
+    pub fn score_moves_for(
+        &mut self,
+        position: &Position,
+        budget: Duration,
+        max_depth: u32,
+    ) -> (Vec<(CompoundMove, i32)>, u32) {
+        let deadline = Instant::now() + budget;
+        self.start(None);
+
+        let max_depth = max_depth.clamp(1, position.available_pieces.len() as u32 + 1);
+        let moves = position_moves(position);
+        let mut scored = (moves.iter().map(|mv| (*mv, 0)).collect(), 0);
+
+        for depth in 1..=max_depth {
+            let scores = moves
+                .iter()
+                .map(|mv| (*mv, self.score_root_move(position, *mv, depth, -INFINITY)))
+                .collect();
+            if self.aborted {
+                log::info!("depth {} aborted after {} nodes", depth, self.nodes);
+                break;
+            }
+
+            scored = (scores, depth);
+            if Instant::now() >= deadline {
+                break;
+            }
+
+            self.deadline = Some(deadline);
+        }
+
+        scored
+    }
+
+    /// Makes every later search end as soon as `stop` is set. A search stopped
+    /// that way returns a move it has not finished searching, only fit to be
+    /// dropped; see `is_stopped`.
+    pub fn set_stop_flag(&mut self, stop: Arc<AtomicBool>) {
+        self.stop = Some(stop);
+    }
+
+    /// Whether the stop flag is set.
+    pub fn is_stopped(&self) -> bool {
+        self.stop
+            .as_ref()
+            .is_some_and(|stop| stop.load(Ordering::Relaxed))
+    }
+
+    /// Splits the root moves over `threads` threads, which share the
+    /// transposition table. The search is sequential with one thread, as it is
+    /// by default.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+        self.pool = None;
+
+        if self.threads > 1 && self.threads != rayon::current_num_threads() {
+            match rayon::ThreadPoolBuilder::new().num_threads(threads).build() {
+                Ok(pool) => self.pool = Some(Arc::new(pool)),
+                Err(e) => {
+                    log::warn!("searching on one thread: {}", e);
+                    self.threads = 1;
+                }
+            }
+        }
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Nodes visited by the last search.
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    /// Transposition table entries found by the last search.
+    pub fn tt_hits(&self) -> u64 {
+        self.tt_hits
+    }
+
+    /// Searches `position` one depth deeper at a time, up to `max_depth`, until
+    /// `budget` runs out. Returns the result of the deepest search that
+    /// completed; the first depth always completes, however long it takes,
+    /// unless the stop flag is set.
+    pub fn run_for(
+        &mut self,
+        position: &Position,
+        budget: Duration,
+        max_depth: u32,
+    ) -> SearchResult {
+        let started = Instant::now();
+        let deadline = started + budget;
+        self.start(None);
+
+        // The game is over once every piece is placed, so deeper searches add nothing
+        let max_depth = max_depth.clamp(1, position.available_pieces.len() as u32 + 1);
+
+        let mut candidates = self.shuffled_root_moves(position);
+        let mut best: Option<SearchResult> = None;
+
+        for depth in 1..=max_depth {
+            let result = match self.search_root(position, &candidates, depth) {
+                Some(result) => result,
+                None => {
+                    log::info!("depth {} aborted after {} nodes", depth, self.nodes);
+                    break;
+                }
+            };
+
+            log::info!(
+                "depth {} score {} nodes {} time {} ms pv {}",
+                depth,
+                result.score,
+                result.nodes,
+                started.elapsed().as_millis(),
+                result
+                    .pv
+                    .iter()
+                    .map(|mv| mv.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            );
+
+            // The best move so far is searched first at the next depth, where
+            // it narrows the window for all the others
+            if let Some(index) = candidates.iter().position(|each| *each == result.best) {
+                let mv = candidates.remove(index);
+                candidates.insert(0, mv);
+            }
+
+            let decisive = is_decisive(result.score);
+            best = Some(result);
+            if decisive || Instant::now() >= deadline {
+                break;
+            }
+
+            self.deadline = Some(deadline);
+        }
+
+        let mut best = best.unwrap_or_else(|| self.stopped(&candidates));
+        best.nodes = self.nodes;
+        best
+    }
+
+    // Result of a search stopped before any depth completed, or of a position
+    // with no move to play
+    fn stopped(&self, candidates: &[CompoundMove]) -> SearchResult {
+        SearchResult {
+            best: candidates.first().copied().unwrap_or(CompoundMove {
+                place: None,
+                give: None,
+            }),
+            score: 0,
+            depth: 0,
+            nodes: self.nodes,
+            pv: vec![],
+        }
+    }
+
+    fn start(&mut self, deadline: Option<Instant>) {
+        self.nodes = 0;
+        self.tt_hits = 0;
+        self.deadline = deadline;
+        self.aborted = false;
+        self.tt.new_search();
+    }
+
+    fn shuffled_root_moves(&mut self, position: &Position) -> Vec<CompoundMove> {
+        let mut candidates = position_moves(position);
+        if let Some(rng) = self.rng.as_mut() {
+            for i in (1..candidates.len()).rev() {
+                let j = rng.rand_range(0..i as u32 + 1) as usize;
+                candidates.swap(i, j);
+            }
+        }
+        candidates
+    }
+
+    /// Searches `candidates` in order, or returns `None` when the deadline
+    /// passes first.
+    fn search_root(
+        &mut self,
+        position: &Position,
+        candidates: &[CompoundMove],
+        depth: u32,
+    ) -> Option<SearchResult> {
+        if self.threads > 1 && candidates.len() > 1 {
+            return match self.pool.clone() {
+                Some(pool) => {
+                    pool.install(|| self.search_root_parallel(position, candidates, depth))
+                }
+                None => self.search_root_parallel(position, candidates, depth),
+            };
+        }
+
+        let mut best: Option<(CompoundMove, i32)> = None;
+        let mut alpha = -INFINITY;
+
+        for candidate in candidates {
+            let score = self.score_root_move(position, *candidate, depth, alpha);
+            if self.aborted {
+                return None;
+            }
+
+            if best.map_or(true, |(_, best_score)| score > best_score) {
+                best = Some((*candidate, score));
+                alpha = alpha.max(score);
+                self.update_pv(0, *candidate);
+            }
+        }
+
+        let (best, score) = best?;
+        Some(SearchResult {
+            best,
+            score,
+            depth,
+            nodes: self.nodes,
+            pv: self.principal_variation(),
+        })
+    }
+
+    /// Searches the first candidate, the best one of the previous depth, on its
+    /// own, then the others at once, each with a copy of the search. The copies
+    /// share the transposition table and the best score found so far.
+    fn search_root_parallel(
+        &mut self,
+        position: &Position,
+        candidates: &[CompoundMove],
+        depth: u32,
+    ) -> Option<SearchResult> {
+        let (first, others) = candidates.split_first()?;
+
+        let first_score = self.score_root_move(position, *first, depth, -INFINITY);
+        if self.aborted {
+            return None;
+        }
+        self.update_pv(0, *first);
+        let mut best = (*first, first_score, self.principal_variation());
+
+        let alpha = AtomicI32::new(first_score);
+        let template = Search {
+            nodes: 0,
+            tt_hits: 0,
+            pool: None,
+            ..self.clone()
+        };
+
+        let results = others
+            .par_iter()
+            .map(|candidate| {
+                let mut search = template.clone();
+                let window = alpha.load(Ordering::Relaxed);
+                let score = search.score_root_move(position, *candidate, depth, window);
+
+                // Only a score above the window is exact, the others are bounds
+                let exact = !search.aborted && score > window;
+                if exact {
+                    alpha.fetch_max(score, Ordering::Relaxed);
+                    search.update_pv(0, *candidate);
+                }
+                let pv = search.principal_variation();
+                let stats = (search.nodes, search.tt_hits, search.aborted);
+                (*candidate, score, exact, pv, stats)
+            })
+            .collect::<Vec<_>>();
+
+        for (candidate, score, exact, pv, (nodes, tt_hits, aborted)) in results {
+            self.nodes += nodes;
+            self.tt_hits += tt_hits;
+            self.aborted |= aborted;
+            if exact && score > best.1 {
+                best = (candidate, score, pv);
+            }
+        }
+        if self.aborted {
+            return None;
+        }
+
+        let (best, score, pv) = best;
+        Some(SearchResult {
+            best,
+            score,
+            depth,
+            nodes: self.nodes,
+            pv,
+        })
+    }
+
+    fn principal_variation(&self) -> Vec<CompoundMove> {
+        self.pv[0][..self.pv_len[0]]
+            .iter()
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    /// Makes `mv` followed by the principal variation of the next ply the
+    /// principal variation at `ply`.
+    fn update_pv(&mut self, ply: usize, mv: CompoundMove) {
+        let (upper, lower) = self.pv.split_at_mut(ply + 1);
+        let len = self.pv_len[ply + 1].max(ply + 1);
+
+        upper[ply][ply] = Some(mv);
+        upper[ply][ply + 1..len].copy_from_slice(&lower[0][ply + 1..len]);
+        self.pv_len[ply] = len;
+    }
+
+    fn is_out_of_time(&mut self) -> bool {
+        if !self.aborted && self.nodes % NODES_PER_CLOCK_CHECK == 0 {
+            let late = self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline);
+            self.aborted = late || self.is_stopped();
+        }
+        self.aborted
+    }
+
+    fn score_root_move(
+        &mut self,
+        position: &Position,
+        candidate: CompoundMove,
+        depth: u32,
+        alpha: i32,
+    ) -> i32 {
+        self.nodes += 1;
+        self.pv_len[1] = 1;
+
+        let mut board = position.board;
+        let mut available = piece_mask(position.available_pieces.iter().copied());
+
+        if let (Some(coor), Phase::PlacePiece { piece, .. }) = (candidate.place, position.phase) {
+            if board.is_winning_placement(coor, piece) {
+                return WIN_SCORE;
+            }
+            board.set(coor, Some(piece));
+            available &= !(1 << piece.bits());
+        }
+
+        match candidate.give {
+            Some(give) => {
+                let node = Node {
+                    board,
+                    piece: give,
+                    available: available & !(1 << give.bits()),
+                };
+                -self.negamax(&node, depth.saturating_sub(1), 1, -INFINITY, -alpha)
+            }
+            // The last piece filled the board without a Quarto
+            None => 0,
+        }
+    }
+
+    /// Score for the side that has to place the piece in hand of `node`.
+    fn negamax(&mut self, node: &Node, depth: u32, ply: u32, mut alpha: i32, mut beta: i32) -> i32 {
+        self.nodes += 1;
+        self.pv_len[ply as usize] = ply as usize;
+
+        if self.is_out_of_time() {
+            return 0;
+        }
+
+        let board = &node.board;
+        if let Some(coor) = board
+            .free_coords()
+            .find(|coor| board.is_winning_placement(*coor, node.piece))
+        {
+            let mv = CompoundMove {
+                place: Some(coor),
+                give: None,
+            };
+            self.pv_len[ply as usize + 1] = ply as usize + 1;
+            self.update_pv(ply as usize, mv);
+            return WIN_SCORE - ply as i32;
+        }
+
+        // The piece in hand is the last one and fills the board without a Quarto
+        if node.available == 0 {
+            return 0;
+        }
+
+        if depth == 0 {
+            return evaluation::evaluate_node(board, node.piece, node.available);
+        }
+
+        let alpha_original = alpha;
+        let hash = if depth >= MIN_TT_DEPTH {
+            Some(symmetry::canonical_hash(board, Some(node.piece)))
+        } else {
+            None
+        };
+
+        if let Some(entry) = hash.and_then(|hash| self.tt.probe(hash)) {
+            self.tt_hits += 1;
+            if entry.depth >= depth {
+                let score = score_from_tt(entry.score, ply);
+                match entry.bound {
+                    Bound::Exact => return score,
+                    Bound::Lower => alpha = alpha.max(score),
+                    Bound::Upper => beta = beta.min(score),
+                }
+                if alpha >= beta {
+                    return score;
+                }
+            }
+        }
+
+        let mut best = -INFINITY;
+        'search: for coor in board.free_coords() {
+            for give in pieces_in(node.available) {
+                let child = node.play(coor, give);
+                let score = -self.negamax(&child, depth - 1, ply + 1, -beta, -alpha);
+                if self.aborted {
+                    return 0;
+                }
+
+                if score > best {
+                    best = score;
+                    if score > alpha {
+                        alpha = score;
+                        self.update_pv(
+                            ply as usize,
+                            CompoundMove {
+                                place: Some(coor),
+                                give: Some(give),
+                            },
+                        );
+                        if alpha >= beta {
+                            break 'search;
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(hash) = hash {
+            let bound = if best <= alpha_original {
+                Bound::Upper
+            } else if best >= beta {
+                Bound::Lower
+            } else {
+                Bound::Exact
+            };
+            self.tt.store(hash, score_to_tt(best, ply), depth, bound);
+        }
+
+        best
+    }
+}
@@ -0,0 +1,211 @@
+//! Symmetries of Quarto positions and a hash that is the same for every
+//! equivalent position.
+//!
+//! Two positions are equivalent when one maps onto the other by
+//! * one of the 32 permutations of the cells that keep every line a line: the
+//!   rotations and reflections of the square, combined with swapping the inner and
+//!   outer rows and columns;
+//! * renaming the pieces by permuting their four attributes and inverting any of
+//!   them, which keeps every shared attribute shared.
+//!
+//! Equivalent positions have the same outcome under perfect play, so the search
+//! can share what it learned about one with all the others.
+
+use crate::{
+    board::{Board, BOARD_CELLS_NUM},
+    piece::Piece,
+    position::Position,
+};
+
+pub const BOARD_SYMMETRIES_NUM: usize = 32;
+
+const ATTRIBUTE_PERMUTATIONS_NUM: usize = 24;
+
+// Permutations of a row or column index that commute with reversing it, so
+// that applying one to the rows and one to the columns keeps the diagonals
+const INDEX_PERMUTATIONS: [[usize; 4]; 8] = [
+    [0, 1, 2, 3],
+    [3, 2, 1, 0],
+    [3, 1, 2, 0],
+    [0, 2, 1, 3],
+    [1, 0, 3, 2],
+    [2, 3, 0, 1],
+    [1, 3, 0, 2],
+    [2, 0, 3, 1],
+];
+
+/// For each symmetry, the cell of the original board that ends up on each cell.
+pub const BOARD_SYMMETRIES: [[usize; BOARD_CELLS_NUM]; BOARD_SYMMETRIES_NUM] = board_symmetries();
+
+const fn board_symmetries() -> [[usize; BOARD_CELLS_NUM]; BOARD_SYMMETRIES_NUM] {
+    let mut symmetries = [[0; BOARD_CELLS_NUM]; BOARD_SYMMETRIES_NUM];
+
+    let mut n = 0;
+    while n < BOARD_SYMMETRIES_NUM {
+        let rows = INDEX_PERMUTATIONS[n % 8];
+        let flip = (n / 8) % 2 == 1;
+        let transpose = n / 16 == 1;
+
+        let mut index = 0;
+        while index < BOARD_CELLS_NUM {
+            let row = rows[index / 4];
+            let col = if flip {
+                3 - rows[index % 4]
+            } else {
+                rows[index % 4]
+            };
+            symmetries[n][index] = if transpose {
+                col * 4 + row
+            } else {
+                row * 4 + col
+            };
+            index += 1;
+        }
+        n += 1;
+    }
+
+    symmetries
+}
+
+// For each permutation of the four attributes, the piece every piece becomes
+const ATTRIBUTE_PERMUTATIONS: [[u8; 16]; ATTRIBUTE_PERMUTATIONS_NUM] = attribute_permutations();
+
+const fn attribute_permutations() -> [[u8; 16]; ATTRIBUTE_PERMUTATIONS_NUM] {
+    let mut permutations = [[0; 16]; ATTRIBUTE_PERMUTATIONS_NUM];
+
+    let mut n = 0;
+    let mut code = 0;
+    while code < 4 * 4 * 4 * 4 {
+        let to = [code % 4, code / 4 % 4, code / 16 % 4, code / 64];
+        if to[0] != to[1]
+            && to[0] != to[2]
+            && to[0] != to[3]
+            && to[1] != to[2]
+            && to[1] != to[3]
+            && to[2] != to[3]
+        {
+            let mut bits = 0;
+            while bits < 16 {
+                let mut image = 0;
+                let mut attribute = 0;
+                while attribute < 4 {
+                    if bits & 1 << attribute != 0 {
+                        image |= 1 << to[attribute];
+                    }
+                    attribute += 1;
+                }
+                permutations[n][bits] = image as u8;
+                bits += 1;
+            }
+            n += 1;
+        }
+        code += 1;
+    }
+
+    permutations
+}
+
+// Random keys for each (slot, piece) of a canonical form, the piece in hand
+// taking the last slot
+const ZOBRIST_KEYS: [[u64; 16]; BOARD_CELLS_NUM + 1] = zobrist_keys();
+
+const fn zobrist_keys() -> [[u64; 16]; BOARD_CELLS_NUM + 1] {
+    let mut keys = [[0; 16]; BOARD_CELLS_NUM + 1];
+
+    // splitmix64
+    let mut state: u64 = 0x5175_6172_746f;
+    let mut slot = 0;
+    while slot < BOARD_CELLS_NUM + 1 {
+        let mut bits = 0;
+        while bits < 16 {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            keys[slot][bits] = z ^ (z >> 31);
+            bits += 1;
+        }
+        slot += 1;
+    }
+
+    keys
+}
+
+// Marks an empty slot in a canonical form; sorts after every piece
+const EMPTY: u8 = 16;
+
+/// Canonical form of a board and piece in hand: the cells in row-major order,
+/// then the piece in hand, `EMPTY` where there is no piece. It is the smallest
+/// such sequence, compared slot by slot, among all the equivalent positions.
+pub fn canonical_form(board: &Board, piece_in_hand: Option<Piece>) -> [u8; BOARD_CELLS_NUM + 1] {
+    let mut slots = [EMPTY; BOARD_CELLS_NUM + 1];
+    for (coor, cell) in board.iter() {
+        slots[coor.index()] = cell.map_or(EMPTY, Piece::bits);
+    }
+    slots[BOARD_CELLS_NUM] = piece_in_hand.map_or(EMPTY, Piece::bits);
+
+    let mut best = [EMPTY; BOARD_CELLS_NUM + 1];
+    let mut first = true;
+
+    for symmetry in BOARD_SYMMETRIES.iter() {
+        let mut form = [EMPTY; BOARD_CELLS_NUM + 1];
+        let mut inversion = None;
+        let mut permutations = [true; ATTRIBUTE_PERMUTATIONS_NUM];
+        let mut better = first;
+
+        for slot in 0..BOARD_CELLS_NUM + 1 {
+            let bits = match slot {
+                BOARD_CELLS_NUM => slots[BOARD_CELLS_NUM],
+                _ => slots[symmetry[slot]],
+            };
+
+            if bits != EMPTY {
+                // The first piece always becomes the piece with no attribute set,
+                // then each piece takes the smallest value that the attribute
+                // permutations still allowed can give it
+                let inverted = bits ^ *inversion.get_or_insert(bits);
+                let smallest = (0..ATTRIBUTE_PERMUTATIONS_NUM)
+                    .filter(|n| permutations[*n])
+                    .map(|n| ATTRIBUTE_PERMUTATIONS[n][inverted as usize])
+                    .min()
+                    .unwrap_or(inverted);
+                for (n, allowed) in permutations.iter_mut().enumerate() {
+                    *allowed &= ATTRIBUTE_PERMUTATIONS[n][inverted as usize] == smallest;
+                }
+                form[slot] = smallest;
+            }
+
+            if !better {
+                if form[slot] > best[slot] {
+                    break;
+                }
+                better = form[slot] < best[slot];
+            }
+        }
+
+        if better {
+            best = form;
+            first = false;
+        }
+    }
+
+    best
+}
+
+/// Zobrist hash of the canonical form: equivalent positions hash the same.
+pub fn canonical_hash(board: &Board, piece_in_hand: Option<Piece>) -> u64 {
+    canonical_form(board, piece_in_hand)
+        .iter()
+        .enumerate()
+        .filter(|(_, bits)| **bits != EMPTY)
+        .fold(0, |hash, (slot, bits)| {
+            hash ^ ZOBRIST_KEYS[slot][*bits as usize]
+        })
+}
+
+impl Position {
+    /// Hash shared by every equivalent position, whichever side is to move.
+    pub fn canonical_hash(&self) -> u64 {
+        canonical_hash(&self.board, self.piece_in_hand())
+    }
+}
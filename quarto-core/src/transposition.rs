@@ -0,0 +1,197 @@
+//! Fixed-size table of search results, keyed by `symmetry::canonical_hash`.
+
+use std::{
+    fmt, mem,
+    sync::atomic::{AtomicU16, AtomicU64, Ordering},
+};
+
+pub const DEFAULT_TABLE_SIZE_MB: usize = 16;
+/// Size for searches run once on their own, such as `endgame::solve`, which
+/// have no later moves to keep results for.
+pub const SMALL_TABLE_SIZE_MB: usize = 2;
+
+/// How the stored score relates to the true score of the position.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Bound {
+    Exact,
+    /// The true score is at least the stored score.
+    Lower,
+    /// The true score is at most the stored score.
+    Upper,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub hash: u64,
+    pub score: i32,
+    /// Compound moves searched below the position.
+    pub depth: u32,
+    pub bound: Bound,
+    generation: u16,
+}
+
+impl Entry {
+    // Everything but the hash, in one word that is never zero: the score in
+    // the low 32 bits, then the depth, the bound and the generation
+    fn pack(&self) -> u64 {
+        let bound = match self.bound {
+            Bound::Exact => 1,
+            Bound::Lower => 2,
+            Bound::Upper => 3,
+        };
+        self.score as u32 as u64
+            | (self.depth.min(u8::MAX as u32) as u64) << 32
+            | bound << 40
+            | (self.generation as u64) << 48
+    }
+
+    fn unpack(hash: u64, data: u64) -> Option<Self> {
+        let bound = match (data >> 40) & 0b11 {
+            1 => Bound::Exact,
+            2 => Bound::Lower,
+            3 => Bound::Upper,
+            _ => return None,
+        };
+        Some(Self {
+            hash,
+            score: data as u32 as i32,
+            depth: (data >> 32) as u8 as u32,
+            bound,
+            generation: (data >> 48) as u16,
+        })
+    }
+}
+
+/// One entry, as its packed data and the hash xor the data. A slot written by
+/// two threads at once holds a key and data that do not match, which reads as
+/// an empty slot rather than a wrong entry.
+#[derive(Default)]
+struct Slot {
+    key: AtomicU64,
+    data: AtomicU64,
+}
+
+/// Search results for positions met before, indexed by the low bits of their
+/// hash. Each slot keeps one entry: a new result replaces the old one when the
+/// old one is from an earlier search, or was searched no deeper.
+///
+/// The table can be shared between threads searching at once, behind an
+/// `Arc`; it takes no locks.
+pub struct TranspositionTable {
+    slots: Vec<Slot>,
+    generation: AtomicU16,
+}
+
+impl fmt::Debug for TranspositionTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TranspositionTable")
+            .field("entries", &self.slots.len())
+            .field("generation", &self.generation)
+            .finish()
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new(DEFAULT_TABLE_SIZE_MB)
+    }
+}
+
+impl TranspositionTable {
+    /// Table using at most `size_mb` megabytes, rounded down to a power of two
+    /// number of entries.
+    pub fn new(size_mb: usize) -> Self {
+        let capacity = (size_mb * 1024 * 1024 / mem::size_of::<Slot>()).max(1);
+        let entries_num = 1 << (usize::BITS - 1 - capacity.leading_zeros());
+
+        Self {
+            slots: (0..entries_num).map(|_| Slot::default()).collect(),
+            generation: AtomicU16::new(0),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Marks the start of a new search, so the entries of earlier searches give
+    /// way to new ones.
+    pub fn new_search(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn clear(&self) {
+        for slot in &self.slots {
+            slot.key.store(0, Ordering::Relaxed);
+            slot.data.store(0, Ordering::Relaxed);
+        }
+    }
+
+    fn slot(&self, hash: u64) -> &Slot {
+        &self.slots[hash as usize & (self.slots.len() - 1)]
+    }
+
+    fn read(&self, hash: u64) -> Option<Entry> {
+        let slot = self.slot(hash);
+        let data = slot.data.load(Ordering::Relaxed);
+        let key = slot.key.load(Ordering::Relaxed);
+
+        if key ^ data == hash {
+            Entry::unpack(hash, data)
+        } else {
+            None
+        }
+    }
+
+    pub fn probe(&self, hash: u64) -> Option<Entry> {
+        self.read(hash)
+    }
+
+    pub fn store(&self, hash: u64, score: i32, depth: u32, bound: Bound) {
+        let generation = self.generation.load(Ordering::Relaxed);
+        let slot = self.slot(hash);
+
+        let old = slot.data.load(Ordering::Relaxed);
+        let replace = match Entry::unpack(0, old) {
+            None => true,
+            Some(old) => old.generation != generation || old.depth <= depth,
+        };
+
+        if replace {
+            let data = Entry {
+                hash,
+                score,
+                depth,
+                bound,
+                generation,
+            }
+            .pack();
+            slot.key.store(hash ^ data, Ordering::Relaxed);
+            slot.data.store(data, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn torn_entries_read_as_empty() {
+        let table = TranspositionTable::new(1);
+        let (first, second) = (3, 3 + table.len() as u64);
+        table.store(first, 10, 2, Bound::Exact);
+        let first_key = table.slot(first).key.load(Ordering::Relaxed);
+
+        // Two threads storing at once: one writes its key, the other its data
+        table.store(second, 20, 5, Bound::Lower);
+        table.slot(first).key.store(first_key, Ordering::Relaxed);
+
+        assert_eq!(table.probe(first), None);
+        assert_eq!(table.probe(second), None);
+    }
+}
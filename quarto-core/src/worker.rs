@@ -0,0 +1,193 @@
+//! Runs an engine on a thread of its own, so that a client, such as a window
+//! that keeps drawing, is not blocked while the engine thinks.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread,
+    time::Instant,
+};
+
+use crate::{
+    board::Coordinate,
+    game::{Move, Phase},
+    piece::Piece,
+    player::Player,
+    position::Position,
+};
+
+/// Job numbers and stop flag shared by a `Worker` and its thread. Jobs are
+/// numbered from 1 as they are sent.
+#[derive(Debug, Clone)]
+pub struct Handshake {
+    stop: Arc<AtomicBool>,
+    /// Jobs up to this one are cancelled.
+    cancelled: Arc<AtomicU64>,
+}
+
+impl Handshake {
+    /// Handshake over `stop`, the flag the engine gives up thinking on.
+    pub fn new(stop: Arc<AtomicBool>) -> Self {
+        Self {
+            stop,
+            cancelled: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Cancels the jobs up to `id`: those not started yet are skipped and the
+    /// one being thought about is stopped.
+    pub fn cancel(&self, id: u64) {
+        self.cancelled.store(id, Ordering::SeqCst);
+        self.stop.store(true, Ordering::SeqCst);
+    }
+
+    /// Called by the worker thread before starting the job `id`. Returns
+    /// `false` when the job is cancelled and has to be skipped.
+    pub fn start(&self, id: u64) -> bool {
+        // Stop is cleared before looking at the cancelled jobs: a job cancelled
+        // in between is skipped here or has stop set again
+        self.stop.store(false, Ordering::SeqCst);
+        id > self.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stop.load(Ordering::SeqCst)
+    }
+}
+
+/// Position to choose a move in. Jobs are numbered, so that answers to
+/// cancelled jobs can be told apart.
+struct Job {
+    id: u64,
+    position: Position,
+}
+
+/// Answer of the worker thread to the job `id`.
+struct Thought {
+    id: u64,
+    mv: Option<Move>,
+}
+
+/// Player whose moves are chosen by an engine on a worker thread. Asking for a
+/// move starts the thinking and returns `None` until the answer comes back.
+pub struct Worker {
+    jobs: Sender<Job>,
+    thoughts: Receiver<Thought>,
+    handshake: Handshake,
+    /// Job being thought about, with its position and when it was sent.
+    pending: Option<(u64, Position, Instant)>,
+    last_id: u64,
+}
+
+impl Worker {
+    /// Starts the thread of `engine`, which has to give up thinking once
+    /// `stop` is set.
+    pub fn spawn(mut engine: Box<dyn Player + Send>, stop: Arc<AtomicBool>) -> Self {
+        let (jobs, job_receiver) = mpsc::channel::<Job>();
+        let (thought_sender, thoughts) = mpsc::channel();
+        let handshake = Handshake::new(stop);
+
+        let thread_handshake = handshake.clone();
+        thread::spawn(move || {
+            while let Ok(job) = job_receiver.recv() {
+                if !thread_handshake.start(job.id) {
+                    continue;
+                }
+
+                let mv = match job.position.phase {
+                    Phase::PlacePiece { .. } => {
+                        engine.choose_placement(&job.position).map(Move::Place)
+                    }
+                    Phase::ChoosePieceForOpponent { .. } => {
+                        engine.choose_piece(&job.position).map(Move::Give)
+                    }
+                };
+
+                let thought = Thought { id: job.id, mv };
+                if thought_sender.send(thought).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            jobs,
+            thoughts,
+            handshake,
+            pending: None,
+            last_id: 0,
+        }
+    }
+
+    /// Move chosen for `position`, once the worker thread has answered. Starts
+    /// the thinking when `position` is not the one being thought about.
+    fn think(&mut self, position: &Position) -> Option<Move> {
+        while let Ok(thought) = self.thoughts.try_recv() {
+            match self.pending.take() {
+                Some((id, asked, started)) if id == thought.id => {
+                    log::info!("thought for {} ms", started.elapsed().as_millis());
+                    if asked == *position {
+                        return thought.mv;
+                    }
+                }
+                pending => self.pending = pending,
+            }
+        }
+
+        match &self.pending {
+            Some((_, asked, _)) if asked == position => {}
+            _ => {
+                self.cancel();
+                self.last_id += 1;
+                let job = Job {
+                    id: self.last_id,
+                    position: position.clone(),
+                };
+                if self.jobs.send(job).is_ok() {
+                    self.pending = Some((self.last_id, position.clone(), Instant::now()));
+                } else {
+                    log::warn!("the worker thread has stopped");
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl Player for Worker {
+    fn choose_placement(&mut self, position: &Position) -> Option<Coordinate> {
+        match self.think(position)? {
+            Move::Place(coor) => Some(coor),
+            Move::Give(_) => None,
+        }
+    }
+
+    fn choose_piece(&mut self, position: &Position) -> Option<Piece> {
+        match self.think(position)? {
+            Move::Give(piece) => Some(piece),
+            Move::Place(_) => None,
+        }
+    }
+
+    fn cancel(&mut self) {
+        if let Some((id, ..)) = self.pending.take() {
+            self.handshake.cancel(id);
+            log::info!("thinking cancelled");
+        }
+    }
+
+    fn is_thinking(&self) -> bool {
+        self.pending.is_some()
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        // The thread ends once its current job stops and the job channel closes
+        self.cancel();
+    }
+}
@@ -0,0 +1,113 @@
+use std::{
+    sync::{atomic::AtomicBool, Arc},
+    time::{Duration, Instant},
+};
+
+use quarto_core::{
+    analysis::{self, Assessment},
+    endgame, notation, Attribute, Color, Line, Search,
+};
+
+mod common;
+
+use common::{drawn_board, position_with};
+
+const LIGHT_ROW: [(&str, &str); 3] = [("a1", "LRTS"), ("b1", "LRTH"), ("c1", "LSSS")];
+
+#[test]
+fn hint_completes_a_quarto() {
+    let hint = analysis::hint(
+        &position_with(&LIGHT_ROW, Some("LSTH")),
+        analysis::HINT_TIME_BUDGET,
+    )
+    .unwrap();
+
+    assert_eq!(hint.place, notation::parse_coordinate("d1").ok());
+    assert_eq!(hint.assessment, Assessment::Winning);
+}
+
+#[test]
+fn hint_hands_over_a_safe_piece() {
+    let position = position_with(&LIGHT_ROW, None);
+    let hint = analysis::hint(&position, analysis::HINT_TIME_BUDGET).unwrap();
+
+    let give = hint.give.unwrap();
+    assert!(!position
+        .board
+        .is_winning_placement(notation::parse_coordinate("d1").unwrap(), give));
+    assert_eq!(hint.place, None);
+}
+
+#[test]
+fn hint_sees_a_lost_position() {
+    // Every piece is Light or Dark, so every piece completes a Quarto on d1
+    let cells = [
+        LIGHT_ROW.as_slice(),
+        &[("d2", "DRSH"), ("d3", "DSTH"), ("d4", "DSSS")],
+    ]
+    .concat();
+    let hint = analysis::hint(&position_with(&cells, None), analysis::HINT_TIME_BUDGET).unwrap();
+
+    assert_eq!(hint.assessment, Assessment::Losing);
+}
+
+#[test]
+fn hints_keep_to_their_budget_in_endgames() {
+    // Nine pieces left: solving this takes well over the budget
+    let position = drawn_board(7, Some(8));
+    let budget = Duration::from_millis(100);
+
+    let started = Instant::now();
+    let hint = analysis::hint(&position, budget).unwrap();
+
+    assert!(started.elapsed() < budget + Duration::from_secs(2));
+    assert!(hint.place.is_some() && hint.give.is_some());
+}
+
+#[test]
+fn hints_prove_the_endgames_they_search_to_the_end() {
+    let position = drawn_board(12, Some(11));
+    let hint = analysis::hint(&position, analysis::HINT_TIME_BUDGET).unwrap();
+    let solution = endgame::solve(&position).unwrap();
+
+    assert!(hint.exact);
+    assert_eq!(hint.assessment, Assessment::from(solution.verdict));
+}
+
+#[test]
+fn stopped_hints_are_dropped() {
+    let mut search = Search::default();
+    search.set_stop_flag(Arc::new(AtomicBool::new(true)));
+    let position = position_with(&LIGHT_ROW, None);
+
+    assert_eq!(
+        analysis::hint_with(&mut search, &position, analysis::HINT_TIME_BUDGET),
+        None
+    );
+}
+
+#[test]
+fn threats_name_the_free_cell_and_the_completing_values() {
+    let position = position_with(&LIGHT_ROW, None);
+    let threats = analysis::threats(&position.board);
+
+    assert_eq!(threats.len(), 1);
+    assert_eq!(threats[0].line, Line::Row(0));
+    assert_eq!(threats[0].cell, notation::parse_coordinate("d1").unwrap());
+    assert_eq!(threats[0].attributes, vec![Attribute::Color(Color::Light)]);
+
+    // Mixed lines and lines with two free cells are not threats
+    let position = common::position_with(&[("a1", "LRTS"), ("b1", "DSSH")], None);
+    assert!(analysis::threats(&position.board).is_empty());
+}
+
+#[test]
+fn pieces_completing_a_threat_are_poisoned() {
+    let position = position_with(&LIGHT_ROW, None);
+    let poisoned = analysis::poisoned_pieces(&position.board, &position.available_pieces);
+
+    assert!(!poisoned.is_empty());
+    for piece in &position.available_pieces {
+        assert_eq!(poisoned.contains(piece), piece.color() == Color::Light);
+    }
+}
@@ -0,0 +1,63 @@
+use quarto_core::arena::{self, EngineSpec, Pairing, Record};
+
+#[test]
+fn engine_specs_read_back_their_display() {
+    for text in ["ai:2", "minimax:4", "mcts:2000"] {
+        assert_eq!(EngineSpec::parse(text).unwrap().to_string(), text);
+    }
+    for text in ["ai", "minimax:0", "mcts:many", "random:1"] {
+        assert_eq!(EngineSpec::parse(text), None);
+    }
+}
+
+#[test]
+fn seeded_games_repeat() {
+    let first = EngineSpec::Minimax(1);
+    let second = EngineSpec::Mcts(50);
+
+    assert_eq!(
+        arena::play_game(first, second, 5, None),
+        arena::play_game(first, second, 5, None)
+    );
+}
+
+#[test]
+fn elo_ratings_follow_the_scores() {
+    assert_eq!(arena::elo_difference(0.5), Some(0.0));
+    assert_eq!(arena::elo_difference(1.0), None);
+
+    let record = |wins, draws, losses| Record {
+        wins,
+        draws,
+        losses,
+    };
+
+    // A 75% score is close to 191 Elo, the extra draw shrinking it a little
+    let pairing = Pairing {
+        first: 0,
+        second: 1,
+        record: record(60, 30, 10),
+    };
+    let ratings = arena::elo_ratings(2, &[pairing]);
+    let expected = arena::elo_difference(0.75).unwrap();
+    assert!((ratings[0] - ratings[1] - expected).abs() < 5.0);
+
+    // Ratings order the engines even when one of them won every game
+    let pairings = [
+        pairing,
+        Pairing {
+            first: 1,
+            second: 2,
+            record: record(60, 30, 10),
+        },
+        Pairing {
+            first: 0,
+            second: 2,
+            record: record(100, 0, 0),
+        },
+    ];
+    let ratings = arena::elo_ratings(3, &pairings);
+
+    assert!(ratings[0] > ratings[1] && ratings[1] > ratings[2]);
+    assert!(ratings.iter().sum::<f64>().abs() < 1e-6);
+}
@@ -0,0 +1,43 @@
+use quarto_core::{Board, Coordinate, Piece};
+
+fn piece(name: &str) -> Piece {
+    Piece::from_name(name).unwrap()
+}
+
+fn board(cells: &[(i8, i8, &str)]) -> Board {
+    let mut board = Board::new();
+    for (row, col, name) in cells {
+        board.set(Coordinate::new(*row, *col), Some(piece(name)));
+    }
+    board
+}
+
+#[test]
+fn winning_placements_are_found_bitwise() {
+    // Three tall pieces in the first column
+    let board = board(&[
+        (0, 0, "Light_Round_Tall_Solid"),
+        (1, 0, "Dark_Square_Tall_Hollow"),
+        (2, 0, "Light_Square_Tall_Solid"),
+    ]);
+    let cell = Coordinate::new(3, 0);
+
+    assert!(board.is_winning_placement(cell, piece("Dark_Round_Tall_Solid")));
+    assert!(!board.is_winning_placement(cell, piece("Dark_Round_Short_Solid")));
+    // A line with a free cell left is not complete
+    assert!(!board.is_winning_placement(Coordinate::new(3, 1), piece("Dark_Round_Tall_Solid")));
+}
+
+#[test]
+fn attributes_shared_by_clear_bits_win_too() {
+    // Three short pieces on the diagonal, whose height bit is clear
+    let board = board(&[
+        (0, 0, "Light_Round_Short_Solid"),
+        (1, 1, "Dark_Square_Short_Hollow"),
+        (2, 2, "Light_Square_Short_Hollow"),
+    ]);
+    let cell = Coordinate::new(3, 3);
+
+    assert!(board.is_winning_placement(cell, piece("Dark_Round_Short_Solid")));
+    assert!(!board.is_winning_placement(cell, piece("Dark_Round_Tall_Solid")));
+}
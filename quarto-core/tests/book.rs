@@ -0,0 +1,118 @@
+use quarto_core::{
+    book::{BookError, OpeningBook},
+    search::CompoundMove,
+    symmetry, Position,
+};
+
+mod common;
+
+use common::position;
+
+fn compound_move(text: &str) -> CompoundMove {
+    text.parse().unwrap()
+}
+
+// Whether `mv` places the piece in hand on a free cell, if there is one,
+// and gives one of the pieces left
+fn is_legal(position: &Position, mv: CompoundMove) -> bool {
+    let placed = match (mv.place, position.piece_in_hand()) {
+        (Some(coor), Some(_)) => position.board.get(coor).is_none(),
+        (None, None) => true,
+        _ => false,
+    };
+    placed && matches!(mv.give, Some(piece) if position.available_pieces.contains(&piece))
+}
+
+// Canonical hash of the position `mv` leads to
+fn successor_hash(position: &Position, mv: CompoundMove) -> u64 {
+    let mut board = position.board;
+    board.set(mv.place.unwrap(), position.piece_in_hand());
+    symmetry::canonical_hash(&board, mv.give)
+}
+
+fn sample() -> OpeningBook {
+    let mut book = OpeningBook::new();
+    let start = Position::initial();
+    assert!(book.add(&start, compound_move("LRSS"), 12));
+    assert!(book.add(&start, compound_move("DSSH"), 3));
+
+    let after = position("LRSS");
+    assert!(book.add(&after, compound_move("a1 DSSH"), 7));
+    assert!(book.add(&after, compound_move("b2 DRTS"), 1));
+    book
+}
+
+#[test]
+fn written_books_read_back() {
+    let book = sample();
+    let text = book.write();
+    let read = OpeningBook::read(&text).unwrap();
+
+    assert_eq!(read.len(), book.len());
+    assert_eq!(read.write(), text);
+    for record in ["", "LRSS"] {
+        let position = position(record);
+        assert_eq!(read.moves(&position), book.moves(&position));
+    }
+
+    let path = std::env::temp_dir().join(format!("quarto-book-{}.txt", std::process::id()));
+    book.save(&path).unwrap();
+    let loaded = OpeningBook::load(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.unwrap().write(), text);
+
+    assert!(matches!(
+        OpeningBook::read(&text.replacen("quarto-book 1", "quarto-book 2", 1)),
+        Err(BookError::UnsupportedVersion(2))
+    ));
+    assert!(matches!(
+        OpeningBook::read("quarto-book 1\n4/4/4/4 LRSS ; a1 ; 1\n"),
+        Err(BookError::Notation { line: 2, .. })
+    ));
+    assert!(!sample().add(&Position::initial(), compound_move("a1 LRSS"), 1));
+}
+
+#[test]
+fn symmetric_positions_share_their_book_moves() {
+    let mut book = OpeningBook::new();
+    let written = position("LRSS a1:LRSS DSSH");
+    assert!(book.add(&written, compound_move("b2 DRTS"), 5));
+
+    // `d4` is a rotation of `a1`: the book move is found through the
+    // canonical hash and given for the position asked about
+    let rotated = position("LRSS d4:LRSS DSSH");
+    assert_ne!(rotated, written);
+    let moves = book.moves(&rotated);
+    assert_eq!(moves.len(), 1);
+
+    let (mv, weight) = moves[0];
+    assert_eq!(weight, 5);
+    assert!(is_legal(&rotated, mv));
+    assert_eq!(
+        successor_hash(&rotated, mv),
+        successor_hash(&written, compound_move("b2 DRTS"))
+    );
+
+    // Adding the rotated move weighs the same book move
+    assert!(book.add(&rotated, mv, 2));
+    assert_eq!(book.len(), 1);
+    assert_eq!(book.moves(&written), vec![(compound_move("b2 DRTS"), 7)]);
+}
+
+#[test]
+fn chosen_moves_are_legal_book_moves() {
+    let book = sample();
+
+    for record in ["", "LRSS"] {
+        let position = position(record);
+        let moves = book.moves(&position);
+
+        for roll in 0..64 {
+            let mv = book.choose(&position, roll).unwrap();
+            assert!(is_legal(&position, mv), "{} is not legal", mv);
+            assert!(moves.iter().any(|(each, _)| *each == mv));
+        }
+    }
+
+    assert_eq!(book.choose(&position("LRSS a1:LRSS DSSH"), 0), None);
+}
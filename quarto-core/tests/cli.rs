@@ -0,0 +1,11 @@
+use quarto_core::cli::arg;
+
+#[test]
+fn flags_take_the_value_after_them() {
+    let args = ["arena", "--games", "20", "--seed", "7"].map(String::from);
+
+    assert_eq!(arg(&args, "--games", 100u64), 20);
+    assert_eq!(arg(&args, "--seed", 1u64), 7);
+    // Missing flags keep their default
+    assert_eq!(arg(&args, "--level", "expert".to_string()), "expert");
+}
@@ -0,0 +1,70 @@
+//! Positions shared by the integration tests. Each test crate uses only some
+//! of them.
+#![allow(dead_code)]
+
+use quarto_core::{notation, Board, Coordinate, Game, Phase, Piece, Position, Turn};
+
+/// A full board without a Quarto, by `Piece::bits` in row-major order.
+pub const DRAWN_BOARD: [u8; 16] = [0, 1, 2, 12, 3, 4, 5, 8, 6, 9, 10, 15, 11, 14, 13, 7];
+
+/// Position reached by playing the game record `record` from the start.
+pub fn position(record: &str) -> Position {
+    Game::from_moves(1, &notation::parse_moves(record).unwrap())
+        .unwrap()
+        .position()
+}
+
+/// Position with the pieces `cells` on the board, every other piece available,
+/// and `piece_in_hand` to be placed by the player.
+pub fn position_with(cells: &[(&str, &str)], piece_in_hand: Option<&str>) -> Position {
+    let mut board = Board::new();
+    for (cell, piece) in cells {
+        board.set(
+            notation::parse_coordinate(cell).unwrap(),
+            Some(notation::parse_piece(piece).unwrap()),
+        );
+    }
+
+    let available_pieces = Piece::all()
+        .into_iter()
+        .filter(|piece| board.iter().all(|(_, cell)| cell != Some(*piece)))
+        .collect();
+
+    let phase = match piece_in_hand {
+        Some(piece) => Phase::PlacePiece {
+            by: Turn::Player,
+            piece: notation::parse_piece(piece).unwrap(),
+        },
+        None => Phase::ChoosePieceForOpponent { by: Turn::Player },
+    };
+
+    Position {
+        board,
+        available_pieces,
+        phase,
+    }
+}
+
+/// `DRAWN_BOARD` with only its first `filled` cells, the others' pieces left
+/// to give, and the piece with bits `piece_in_hand` to be placed by the player.
+pub fn drawn_board(filled: usize, piece_in_hand: Option<u8>) -> Position {
+    let mut board = Board::new();
+    for (index, bits) in DRAWN_BOARD[..filled].iter().enumerate() {
+        board.set(Coordinate::from_index(index), Some(Piece::from_bits(*bits)));
+    }
+
+    Position {
+        board,
+        available_pieces: DRAWN_BOARD[filled..]
+            .iter()
+            .map(|bits| Piece::from_bits(*bits))
+            .collect(),
+        phase: match piece_in_hand {
+            Some(bits) => Phase::PlacePiece {
+                by: Turn::Player,
+                piece: Piece::from_bits(bits),
+            },
+            None => Phase::ChoosePieceForOpponent { by: Turn::Player },
+        },
+    }
+}
@@ -0,0 +1,37 @@
+use quarto_core::Difficulty;
+
+#[test]
+fn difficulties_read_from_names_and_levels() {
+    for difficulty in Difficulty::ALL {
+        assert_eq!(Difficulty::parse(difficulty.name()), Some(difficulty));
+        assert_eq!(
+            Difficulty::parse(&difficulty.level().to_string()),
+            Some(difficulty)
+        );
+        assert_eq!(Difficulty::from_level(difficulty.level()), difficulty);
+    }
+    assert_eq!(Difficulty::parse("hARD"), Some(Difficulty::Hard));
+    for text in ["0", "7", "impossible", ""] {
+        assert_eq!(Difficulty::parse(text), None);
+    }
+
+    assert_eq!(Difficulty::from_level(0), Difficulty::Beginner);
+    assert_eq!(Difficulty::from_level(99), Difficulty::Perfect);
+}
+
+#[test]
+fn settings_never_get_weaker_up_the_table() {
+    for pair in Difficulty::ALL.windows(2) {
+        let (easier, harder) = (pair[0].settings(), pair[1].settings());
+        assert!(harder.depth >= easier.depth);
+        assert!(harder.time_budget >= easier.time_budget);
+        assert!(harder.noise <= easier.noise);
+        assert!(harder.blunder_percent <= easier.blunder_percent);
+        assert!(harder.solve_endgames || !easier.solve_endgames);
+        assert!(harder.mcts_iterations >= easier.mcts_iterations);
+    }
+    assert_ne!(
+        Difficulty::Beginner.settings(),
+        Difficulty::Perfect.settings()
+    );
+}
@@ -0,0 +1,80 @@
+use quarto_core::{
+    endgame::{self, Verdict},
+    evaluation::{self, LOST_PLACEMENT_SCORE},
+    Coordinate, Game, Players, Position, AI,
+};
+
+mod common;
+
+use common::drawn_board;
+
+#[test]
+fn won_endgames_are_solved() {
+    // 0111 completes the first column, whose pieces all have a clear fourth bit
+    let solution = endgame::solve(&drawn_board(12, Some(7))).unwrap();
+
+    assert_eq!(solution.verdict, Verdict::Win { moves: 1 });
+    assert_eq!(solution.best.place, Some(Coordinate::new(3, 0)));
+    assert_eq!(solution.best.give, None);
+}
+
+#[test]
+fn lost_endgames_are_solved() {
+    // Each of the four pieces left completes a column or a diagonal, so the
+    // other side wins with its first compound move, the second from now
+    let position = drawn_board(12, None);
+    let solution = endgame::solve(&position).unwrap();
+
+    assert_eq!(solution.verdict, Verdict::Loss { moves: 2 });
+    assert_eq!(evaluation::evaluate(&position), LOST_PLACEMENT_SCORE);
+}
+
+#[test]
+fn drawn_endgames_are_solved() {
+    let solution = endgame::solve(&drawn_board(15, Some(7))).unwrap();
+
+    assert_eq!(solution.verdict, Verdict::Draw);
+    assert_eq!(solution.best.place, Some(Coordinate::new(3, 3)));
+
+    // Too many pieces left, or none
+    assert_eq!(endgame::solve(&Position::initial()), None);
+    assert_eq!(endgame::solve(&drawn_board(16, None)), None);
+}
+
+#[test]
+fn evaluation_agrees_with_solved_losses() {
+    let mut positions = vec![];
+    for seed in 0..4 {
+        let mut game = Game::init(1);
+        let mut players = Players::new(
+            Box::new(AI::with_seed(1, seed)),
+            Box::new(AI::with_seed(1, seed + 100)),
+        );
+        while !game.is_over() {
+            let position = game.position();
+            // Deep enough for poisoned boards, shallow enough to solve quickly
+            if position.piece_in_hand().is_none() && position.available_pieces.len() <= 7 {
+                positions.push(position);
+            }
+            players.step(&mut game).unwrap();
+        }
+    }
+    assert!(!positions.is_empty());
+
+    // The evaluation calls a position lost when no piece is safe to give,
+    // exactly when the other side is proven to win with its next move
+    let mut lost = 0;
+    for position in positions {
+        let verdict = endgame::solve(&position).unwrap().verdict;
+        let evaluated_lost = evaluation::evaluate(&position) == LOST_PLACEMENT_SCORE;
+
+        assert_eq!(
+            evaluated_lost,
+            verdict == Verdict::Loss { moves: 2 },
+            "{}",
+            position
+        );
+        lost += evaluated_lost as usize;
+    }
+    assert!(lost > 0, "no lost position was reached");
+}
@@ -0,0 +1,102 @@
+use quarto_core::{
+    evaluation::{self, LOST_PLACEMENT_SCORE},
+    notation, Attribute, Board, Color, Position,
+};
+
+mod common;
+
+use common::position_with;
+
+// Three Light pieces on row 1 and three Dark pieces on column d, both with d1 free
+const LIGHT_ROW_AND_DARK_COLUMN: [(&str, &str); 6] = [
+    ("a1", "LRTS"),
+    ("b1", "LRTH"),
+    ("c1", "LSSS"),
+    ("d2", "DRSH"),
+    ("d3", "DSTH"),
+    ("d4", "DSSS"),
+];
+
+#[test]
+fn line_values_sum_attributes() {
+    let empty = Position::initial();
+    assert_eq!(evaluation::line_values(&empty.board), [[0; 4]; 10]);
+
+    let position = position_with(&[("a1", "LRTS"), ("b1", "DRTS")], None);
+    let values = evaluation::line_values(&position.board);
+
+    // Color, shape, height, fill; Dark, Square, Tall and Hollow count as 1
+    assert_eq!(values[0], [0, -2, 2, -2]);
+    assert_eq!(values[4], [-1, -1, 1, -1]);
+    assert_eq!(values[5], [1, -1, 1, -1]);
+    assert_eq!(values[8], [-1, -1, 1, -1]);
+    assert_eq!(values[9], [0, 0, 0, 0]);
+}
+
+#[test]
+fn best_place_is_in_the_strongest_line() {
+    let position = position_with(&[("a1", "LRTS"), ("b1", "LRTH")], None);
+    let values = evaluation::line_values(&position.board);
+
+    let c1 = notation::parse_coordinate("c1").unwrap();
+    for attribute in 0..3 {
+        assert_eq!(
+            evaluation::best_place_for_attribute(&position.board, &values, attribute),
+            Some(c1)
+        );
+    }
+    // The fills differ, so the strongest line for fill is the first one through a1
+    assert_eq!(
+        evaluation::best_place_for_attribute(&position.board, &values, 3),
+        notation::parse_coordinate("a2").ok()
+    );
+}
+
+#[test]
+fn winning_attributes_come_from_three_shared_values() {
+    let light_row = position_with(&LIGHT_ROW_AND_DARK_COLUMN[..3], None);
+    assert_eq!(
+        evaluation::winning_attributes(&light_row.board),
+        vec![Attribute::Color(Color::Light)]
+    );
+
+    let both = position_with(&LIGHT_ROW_AND_DARK_COLUMN, None);
+    assert_eq!(
+        evaluation::winning_attributes(&both.board),
+        vec![
+            Attribute::Color(Color::Dark),
+            Attribute::Color(Color::Light)
+        ]
+    );
+
+    assert!(evaluation::winning_attributes(&Board::new()).is_empty());
+}
+
+#[test]
+fn placement_leaving_both_values_of_an_attribute_scores_zero() {
+    let cells = &LIGHT_ROW_AND_DARK_COLUMN[3..];
+    let position = position_with(
+        &[cells, &[("a1", "LRTS"), ("b1", "LRTH")]].concat(),
+        Some("LSSH"),
+    );
+    let board = &position.board;
+    let piece = notation::parse_piece("LSSH").unwrap();
+
+    // c1 makes three Light pieces in row 1 next to three Dark ones in column d
+    let c1 = notation::parse_coordinate("c1").unwrap();
+    assert_eq!(evaluation::eval_placement(board, c1, piece), 0);
+
+    let a4 = notation::parse_coordinate("a4").unwrap();
+    assert!(evaluation::eval_placement(board, a4, piece) > 0);
+}
+
+#[test]
+fn evaluation_spots_positions_without_a_safe_piece() {
+    // Every piece is Light or Dark, so every piece completes a Quarto on d1
+    let lost = position_with(&LIGHT_ROW_AND_DARK_COLUMN, None);
+    assert_eq!(evaluation::evaluate(&lost), LOST_PLACEMENT_SCORE);
+
+    // Only Light pieces complete row 1, so a Dark piece is safe to give
+    let open = position_with(&LIGHT_ROW_AND_DARK_COLUMN[..3], None);
+    assert!(evaluation::evaluate(&open) > LOST_PLACEMENT_SCORE);
+}
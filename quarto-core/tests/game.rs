@@ -0,0 +1,295 @@
+use quarto_core::{
+    Attribute, Board, Color, Coordinate, Game, GameError, GameOutcome, Height, Line, Move, Phase,
+    PhaseKind, Piece, Position, Shape, Turn,
+};
+
+fn piece(name: &str) -> Piece {
+    Piece::from_name(name).unwrap()
+}
+
+fn board(cells: &[(i8, i8, &str)]) -> Board {
+    let mut board = Board::new();
+    for (row, col, name) in cells {
+        board.set(Coordinate::new(*row, *col), Some(piece(name)));
+    }
+    board
+}
+
+#[test]
+fn rules_build_without_graphics() {
+    // The core is the part of the game that runs headless, in tests and tools
+    let manifest = include_str!("../Cargo.toml");
+    for dependency in ["wgpu", "winit", "cgmath", "image", "tobj"] {
+        assert!(
+            !manifest
+                .lines()
+                .any(|line| line.trim_start().starts_with(dependency)),
+            "quarto-core depends on {}",
+            dependency
+        );
+    }
+}
+
+#[test]
+fn game_state_credits_a_completed_line() {
+    let mut game = Game::init(1);
+    assert_eq!(game.check_game_state(Turn::Player), GameOutcome::InProgress);
+
+    game.board = board(&[
+        (0, 0, "Light_Round_Tall_Solid"),
+        (1, 0, "Dark_Square_Tall_Hollow"),
+        (2, 0, "Light_Square_Tall_Solid"),
+    ]);
+    assert_eq!(game.check_game_state(Turn::Player), GameOutcome::InProgress);
+
+    game.board
+        .set(Coordinate::new(3, 0), Some(piece("Dark_Round_Tall_Solid")));
+    match game.check_game_state(Turn::Opponent) {
+        GameOutcome::Won {
+            by,
+            line,
+            attributes,
+            ..
+        } => {
+            assert_eq!(by, Turn::Opponent);
+            assert_eq!(line, Line::Col(0));
+            assert_eq!(attributes, vec![Attribute::Height(Height::Tall)]);
+        }
+        outcome => panic!("expected a win, got {:?}", outcome),
+    }
+}
+
+#[test]
+fn filling_the_board_without_a_quarto_is_a_draw() {
+    // No line of this board shares an attribute
+    let bits = [0, 1, 2, 12, 3, 4, 5, 8, 6, 9, 10, 15, 11, 14, 13, 7];
+    let mut game = Game::init(1);
+
+    for (index, bits) in bits.into_iter().enumerate() {
+        assert_eq!(game.outcome, GameOutcome::InProgress);
+        game.apply(Move::Give(Piece::from_bits(bits))).unwrap();
+        game.apply(Move::Place(Coordinate::from_index(index)))
+            .unwrap();
+    }
+
+    assert!(game.board.is_full());
+    assert_eq!(game.outcome, GameOutcome::Draw);
+    assert!(game.is_over());
+    assert!(game.legal_moves().is_empty());
+}
+
+#[test]
+fn winning_move_reports_the_line_and_its_shared_attributes() {
+    let game = |moves: &[Move]| Game::from_moves(1, moves).unwrap();
+    let give = |name| Move::Give(piece(name));
+    let place = |row, col| Move::Place(Coordinate::new(row, col));
+
+    // The player completes the first row with four light round pieces
+    let game = game(&[
+        give("Light_Round_Tall_Solid"),
+        place(0, 0),
+        give("Light_Round_Short_Hollow"),
+        place(0, 1),
+        give("Light_Round_Tall_Hollow"),
+        place(0, 2),
+        give("Light_Round_Short_Solid"),
+        place(0, 3),
+    ]);
+
+    assert_eq!(
+        game.outcome,
+        GameOutcome::Won {
+            by: Turn::Player,
+            line: Line::Row(0),
+            coords: Line::Row(0).coords(),
+            attributes: vec![
+                Attribute::Color(Color::Light),
+                Attribute::Shape(Shape::Round),
+            ],
+        }
+    );
+    assert!(game.is_over());
+}
+
+#[test]
+fn only_the_first_of_several_completed_lines_is_reported() {
+    // Placing a dark piece on a4 completes both the last row and the first
+    // column, each of dark pieces
+    let mut game = Game::init(1);
+    game.board = board(&[
+        (3, 1, "Dark_Round_Short_Solid"),
+        (3, 2, "Dark_Square_Short_Hollow"),
+        (3, 3, "Dark_Round_Tall_Hollow"),
+        (0, 0, "Dark_Square_Tall_Solid"),
+        (1, 0, "Dark_Round_Short_Hollow"),
+        (2, 0, "Dark_Square_Tall_Hollow"),
+    ]);
+    game.board
+        .set(Coordinate::new(3, 0), Some(piece("Dark_Round_Tall_Solid")));
+
+    match game.check_game_state(Turn::Player) {
+        GameOutcome::Won { line, .. } => assert_eq!(line, Line::Row(3)),
+        outcome => panic!("expected a win, got {:?}", outcome),
+    }
+    assert!(game.check_line(Line::Col(0)).is_some());
+}
+
+#[test]
+fn phases_alternate_between_giving_and_placing() {
+    let first = piece("Light_Round_Tall_Solid");
+    let second = piece("Dark_Square_Short_Hollow");
+    let mut game = Game::init(1);
+
+    assert_eq!(
+        game.phase,
+        Phase::ChoosePieceForOpponent { by: Turn::Player }
+    );
+    assert_eq!(game.piece_in_hand(), None);
+
+    game.apply(Move::Give(first)).unwrap();
+    assert_eq!(
+        game.phase,
+        Phase::PlacePiece {
+            by: Turn::Opponent,
+            piece: first
+        }
+    );
+    assert_eq!(game.turn(), Turn::Opponent);
+    assert_eq!(game.piece_in_hand(), Some(first));
+
+    // The side that placed a piece chooses the next one
+    game.apply(Move::Place(Coordinate::new(1, 2))).unwrap();
+    assert_eq!(
+        game.phase,
+        Phase::ChoosePieceForOpponent { by: Turn::Opponent }
+    );
+    assert_eq!(game.phase.kind(), PhaseKind::ChoosePieceForOpponent);
+
+    game.apply(Move::Give(second)).unwrap();
+    assert_eq!(
+        game.phase,
+        Phase::PlacePiece {
+            by: Turn::Player,
+            piece: second
+        }
+    );
+    assert_eq!(game.phase.kind(), PhaseKind::PlacePiece);
+}
+
+#[test]
+fn rejected_moves_leave_the_game_untouched() {
+    let first = piece("Light_Round_Tall_Solid");
+    let mut game = Game::init(1);
+
+    assert_eq!(
+        game.apply(Move::Place(Coordinate::new(0, 0))),
+        Err(GameError::WrongPhase {
+            expected: PhaseKind::PlacePiece,
+            actual: Phase::ChoosePieceForOpponent { by: Turn::Player },
+        })
+    );
+
+    game.apply(Move::Give(first)).unwrap();
+    assert_eq!(
+        game.apply(Move::Place(Coordinate::new(4, 0))),
+        Err(GameError::OutOfBoard(Coordinate::new(4, 0)))
+    );
+    game.apply(Move::Place(Coordinate::new(0, 0))).unwrap();
+
+    // The piece is on the board now
+    assert_eq!(
+        game.apply(Move::Give(first)),
+        Err(GameError::PieceNotAvailable(first))
+    );
+    game.apply(Move::Give(piece("Light_Round_Short_Solid")))
+        .unwrap();
+    let before = game.position();
+    assert_eq!(
+        game.apply(Move::Place(Coordinate::new(0, 0))),
+        Err(GameError::CellOccupied(Coordinate::new(0, 0)))
+    );
+    assert_eq!(game.position(), before);
+    assert_eq!(game.history().len(), 3);
+
+    // Four light round pieces fill the first row
+    game.apply(Move::Place(Coordinate::new(0, 1))).unwrap();
+    game.apply(Move::Give(piece("Light_Round_Tall_Hollow")))
+        .unwrap();
+    game.apply(Move::Place(Coordinate::new(0, 2))).unwrap();
+    game.apply(Move::Give(piece("Light_Round_Short_Hollow")))
+        .unwrap();
+    game.apply(Move::Place(Coordinate::new(0, 3))).unwrap();
+
+    assert!(game.is_over());
+    assert_eq!(
+        game.apply(Move::Give(piece("Dark_Round_Tall_Solid"))),
+        Err(GameError::GameOver)
+    );
+}
+
+#[test]
+fn undo_and_redo_walk_the_history() {
+    let moves = [
+        Move::Give(piece("Light_Round_Tall_Solid")),
+        Move::Place(Coordinate::new(2, 2)),
+        Move::Give(piece("Dark_Square_Short_Hollow")),
+        Move::Place(Coordinate::new(0, 0)),
+    ];
+    let mut game = Game::from_moves(1, &moves).unwrap();
+    let end = game.position();
+
+    assert!(!game.can_redo());
+    assert_eq!(game.undo(), Some(moves[3]));
+    assert_eq!(game.undo(), Some(moves[2]));
+    assert_eq!(
+        game.position(),
+        Game::from_moves(1, &moves[..2]).unwrap().position()
+    );
+    assert_eq!(game.redo_moves(), moves[2..].to_vec());
+
+    assert_eq!(game.redo(), Some(moves[2]));
+    assert_eq!(game.redo(), Some(moves[3]));
+    assert_eq!(game.redo(), None);
+    assert_eq!(game.position(), end);
+    assert_eq!(game.history(), moves);
+
+    // Playing a new move drops the moves taken back
+    while game.undo().is_some() {}
+    assert_eq!(game.position(), Position::initial());
+    game.apply(Move::Give(piece("Dark_Round_Tall_Solid")))
+        .unwrap();
+    assert!(!game.can_redo());
+}
+
+#[test]
+fn undo_stops_at_the_starting_position() {
+    let opening = [
+        Move::Give(piece("Light_Round_Tall_Solid")),
+        Move::Place(Coordinate::new(0, 0)),
+        Move::Give(piece("Light_Round_Short_Hollow")),
+        Move::Place(Coordinate::new(0, 1)),
+        Move::Give(piece("Light_Round_Tall_Hollow")),
+        Move::Place(Coordinate::new(0, 2)),
+    ];
+    let start = Game::from_moves(1, &opening).unwrap().position();
+    let moves = [
+        Move::Give(piece("Dark_Square_Short_Solid")),
+        Move::Place(Coordinate::new(3, 3)),
+        Move::Give(piece("Light_Round_Short_Solid")),
+        Move::Place(Coordinate::new(0, 3)),
+    ];
+    let mut game = Game::from_position_and_moves(1, start.clone(), &moves).unwrap();
+    assert!(game.is_over());
+    let end = game.position();
+
+    assert_eq!(game.undo(), Some(moves[3]));
+    assert_eq!(game.outcome, GameOutcome::InProgress);
+    while game.undo().is_some() {}
+    assert_eq!(game.position(), start);
+    assert_eq!(game.start(), &start);
+    assert!(!game.can_undo());
+
+    while game.redo().is_some() {}
+    assert_eq!(game.position(), end);
+    assert!(game.is_over());
+}
@@ -0,0 +1,91 @@
+use quarto_core::{
+    analysis, mcts::Mcts, Board, Color, CompoundMove, Coordinate, Phase, Piece, Player, Position,
+    Turn,
+};
+
+mod common;
+
+use common::{drawn_board, position};
+
+/// Position where the player chooses a piece, with three pieces of the same
+/// color as `piece` in the first row and its last cell free.
+fn row_of_three_like(piece: Piece) -> Position {
+    let placed = Piece::all()
+        .into_iter()
+        .filter(|other| *other != piece && other.color() == piece.color())
+        .take(3)
+        .collect::<Vec<_>>();
+
+    let mut board = Board::new();
+    for (col, other) in placed.iter().enumerate() {
+        board.set(Coordinate::new(0, col as i8), Some(*other));
+    }
+
+    Position {
+        board,
+        available_pieces: Piece::all()
+            .into_iter()
+            .filter(|other| !placed.contains(other))
+            .collect(),
+        phase: Phase::ChoosePieceForOpponent { by: Turn::Player },
+    }
+}
+
+#[test]
+fn takes_an_immediate_win() {
+    // Three light round pieces in the first row, and a fourth one in hand
+    let position = position("LRTS a1 LRSH b1 LRTH c1 LRSS");
+
+    assert_eq!(
+        Mcts::new(2000, 1).select_place(&position),
+        Coordinate::new(0, 3)
+    );
+}
+
+#[test]
+fn does_not_give_a_winning_piece() {
+    let position = row_of_three_like(Piece::from_bits(0));
+    let piece = Mcts::new(2000, 1).select_piece(&position);
+
+    assert!(!analysis::is_poisoned(&position.board, piece), "{}", piece);
+    assert_eq!(piece.color(), Color::Dark);
+}
+
+#[test]
+fn planned_pieces_are_only_given_on_their_board() {
+    let placing = position("LRTS c3 DSSH");
+    let mut mcts = Mcts::new(2000, 1);
+    let place = mcts.select_place(&placing);
+
+    // The piece planned for the board after the placement
+    let mut after = placing.clone();
+    after.board.set(place, after.piece_in_hand());
+    after
+        .available_pieces
+        .retain(|piece| Some(*piece) != placing.piece_in_hand());
+    after.phase = Phase::ChoosePieceForOpponent { by: Turn::Opponent };
+    let planned = mcts.clone().select_piece(&after);
+
+    // On another board, where that piece lets the other side win
+    let other = row_of_three_like(planned);
+    let piece = mcts.select_piece(&other);
+
+    assert!(!analysis::is_poisoned(&other.board, piece), "{}", piece);
+}
+
+#[test]
+fn searches_without_moves_return_no_move() {
+    // The board is full and no piece is left to give
+    let position = drawn_board(16, None);
+    let mut mcts = Mcts::new(100, 1);
+
+    assert_eq!(
+        mcts.run(&position),
+        CompoundMove {
+            place: None,
+            give: None,
+        }
+    );
+    assert_eq!(mcts.choose_piece(&position), None);
+    assert_eq!(mcts.choose_placement(&position), None);
+}
@@ -0,0 +1,130 @@
+use quarto_core::{
+    notation::{self, NotationError},
+    Coordinate, Game, Move, Piece, Position,
+};
+
+#[test]
+fn pieces_round_trip() {
+    for piece in Piece::all() {
+        let text = notation::format_piece(piece);
+        assert_eq!(notation::parse_piece(&text), Ok(piece));
+    }
+
+    assert_eq!(
+        notation::parse_piece("LRTS").unwrap().name(),
+        "Light_Round_Tall_Solid"
+    );
+    assert_eq!(
+        notation::parse_piece("DSSH").unwrap().name(),
+        "Dark_Square_Short_Hollow"
+    );
+}
+
+#[test]
+fn cells_round_trip() {
+    for index in 0..16 {
+        let coor = Coordinate::from_index(index);
+        let text = notation::format_coordinate(coor);
+        assert_eq!(notation::parse_coordinate(&text), Ok(coor));
+    }
+
+    assert_eq!(notation::parse_coordinate("a1"), Ok(Coordinate::new(0, 0)));
+    assert_eq!(notation::parse_coordinate("d2"), Ok(Coordinate::new(1, 3)));
+    assert!(notation::parse_coordinate("e1").is_err());
+    assert!(notation::parse_coordinate("a5").is_err());
+}
+
+#[test]
+fn game_record_round_trips() {
+    let record = "LRTS c3:LRTS DSSH a1:DSSH LSTH d4:LSTH";
+    let moves = notation::parse_moves(record).unwrap();
+
+    assert_eq!(moves[1], Move::Place(Coordinate::new(2, 2)));
+    assert_eq!(notation::format_moves(&moves), record);
+
+    let game = Game::from_moves(1, &moves).unwrap();
+    assert_eq!(notation::format_moves(game.history()), record);
+}
+
+#[test]
+fn placement_must_name_the_piece_in_hand() {
+    assert_eq!(
+        notation::parse_moves("LRTS c3:DSSH"),
+        Err(NotationError::PieceMismatch {
+            expected: notation::parse_piece("LRTS").unwrap(),
+            found: notation::parse_piece("DSSH").unwrap(),
+        })
+    );
+    assert_eq!(
+        notation::parse_moves("LRTS c3"),
+        Ok(vec![
+            Move::Give(notation::parse_piece("LRTS").unwrap()),
+            Move::Place(Coordinate::new(2, 2)),
+        ])
+    );
+}
+
+#[test]
+fn initial_position_round_trips() {
+    let text = Position::initial().to_string();
+
+    assert!(text.starts_with("4/4/4/4 LRTS,LRTH,"));
+    assert!(text.ends_with(" - P"));
+    assert_eq!(text.parse::<Position>(), Ok(Position::initial()));
+}
+
+#[test]
+fn mid_game_positions_round_trip() {
+    let moves = notation::parse_moves("LRTS c3:LRTS DSSH a1:DSSH LSTH d3:LSTH DRTS").unwrap();
+
+    for played in 0..=moves.len() {
+        let game = Game::from_moves(1, &moves[..played]).unwrap();
+        let position = game.position();
+        let text = position.to_string();
+
+        assert_eq!(text.parse::<Position>(), Ok(position), "{}", text);
+    }
+
+    let game = Game::from_moves(1, &moves).unwrap();
+    assert_eq!(
+        game.position().to_string().split(' ').next(),
+        Some("DSSH3/4/2LRTSLSTH/4")
+    );
+    assert!(game.position().to_string().ends_with(" DRTS P"));
+}
+
+#[test]
+fn invalid_positions_are_rejected() {
+    let initial = Position::initial().to_string();
+
+    // Missing field
+    assert!(initial.trim_end_matches(" P").parse::<Position>().is_err());
+    // Piece both on the board and available
+    assert!(initial.replacen("4/", "LRTS3/", 1).parse::<Position>().is_err());
+    // Too many cells in a row
+    assert!(initial.replacen("4/", "5/", 1).parse::<Position>().is_err());
+    // Piece in hand not available
+    let placed = "LRTS3/4/4/4 LRTH,LSTS,LSTH,LSSS,LSSH,LRSS,LRSH,DRSS,DRSH,DSSS,DSSH,DSTS,DSTH,DRTS,DRTH LRTS O";
+    assert!(placed.parse::<Position>().is_err());
+}
+
+#[test]
+fn malformed_moves_are_rejected_by_their_shape() {
+    let invalid_move = |text: &str| Err(NotationError::InvalidMove(text.to_string()));
+
+    assert_eq!(notation::parse_move("a1:X", None), invalid_move("a1:X"));
+    assert_eq!(
+        notation::parse_move("e1:LRTS", None),
+        invalid_move("e1:LRTS")
+    );
+    assert_eq!(notation::parse_move("a1c3", None), invalid_move("a1c3"));
+    assert_eq!(notation::parse_move("", None), invalid_move(""));
+    assert_eq!(
+        notation::parse_move("LRTX", None),
+        Err(NotationError::InvalidPiece("LRTX".to_string()))
+    );
+    assert_eq!(
+        notation::parse_move("d2", None),
+        Ok(Move::Place(Coordinate::new(1, 3)))
+    );
+}
@@ -0,0 +1,73 @@
+use quarto_core::{Attribute, Color, Fill, Height, Piece, Shape, PIECE_NAMES};
+
+#[test]
+fn names_round_trip() {
+    for name in PIECE_NAMES {
+        assert_eq!(Piece::from_name(name).unwrap().name(), name);
+    }
+    for bits in 0..16 {
+        let piece = Piece::from_bits(bits);
+        assert_eq!(Piece::from_name(piece.name()), Some(piece));
+    }
+    assert_eq!(Piece::from_name("Light_Round_Tall"), None);
+
+    let pieces = Piece::all();
+    assert_eq!(pieces.len(), 16);
+    assert!(pieces
+        .iter()
+        .enumerate()
+        .all(|(i, piece)| pieces[..i].iter().all(|other| other != piece)));
+}
+
+#[test]
+fn attributes_match_the_names() {
+    let piece = Piece::from_name("Dark_Square_Short_Hollow").unwrap();
+
+    assert_eq!(
+        piece,
+        Piece::new(Color::Dark, Shape::Square, Height::Short, Fill::Hollow)
+    );
+    assert_eq!(piece.bits(), Piece::COLOR | Piece::SHAPE | Piece::FILL);
+    assert_eq!(
+        piece.attributes(Piece::ALL_ATTRIBUTES),
+        vec![
+            Attribute::Color(Color::Dark),
+            Attribute::Shape(Shape::Square),
+            Attribute::Height(Height::Short),
+            Attribute::Fill(Fill::Hollow),
+        ]
+    );
+}
+
+#[test]
+fn shared_attributes_count_set_and_clear_bits() {
+    let piece = |name| Piece::from_name(name).unwrap();
+
+    // Every piece is light, two are round and tall, the others square and short
+    let light = [
+        piece("Light_Round_Tall_Solid"),
+        piece("Light_Square_Short_Hollow"),
+        piece("Light_Round_Tall_Hollow"),
+        piece("Light_Square_Short_Solid"),
+    ];
+    assert_eq!(Piece::shared_attributes(&light), Piece::COLOR);
+
+    // Every piece is tall and hollow, whatever its color and shape
+    let tall_hollow = [
+        piece("Light_Round_Tall_Hollow"),
+        piece("Dark_Round_Tall_Hollow"),
+        piece("Light_Square_Tall_Hollow"),
+        piece("Dark_Square_Tall_Hollow"),
+    ];
+    assert_eq!(
+        Piece::shared_attributes(&tall_hollow),
+        Piece::HEIGHT | Piece::FILL
+    );
+
+    // A piece and its opposite share nothing
+    let opposites = [
+        piece("Light_Round_Short_Solid"),
+        piece("Dark_Square_Tall_Hollow"),
+    ];
+    assert_eq!(Piece::shared_attributes(&opposites), 0);
+}
@@ -0,0 +1,61 @@
+use std::{
+    net::{TcpListener, TcpStream},
+    time::Duration,
+};
+
+use quarto_core::{mcts::Mcts, player::RemotePlayer, Game, Players, AI};
+
+#[test]
+fn engines_play_a_whole_game() {
+    let mut game = Game::init(1);
+    let mut players = Players::new(Box::new(AI::with_seed(1, 7)), Box::new(Mcts::new(500, 7)));
+
+    let played = players.play(&mut game).unwrap();
+
+    assert!(game.is_over());
+    assert_eq!(played, game.history().len());
+}
+
+#[test]
+fn seeded_ais_play_the_same_game_again() {
+    let play = |seed| {
+        let ai = || {
+            // Fixed depth, so only the seed decides between the moves
+            let mut ai = AI::with_seed(1, seed);
+            ai.time_budget = Duration::from_secs(60);
+            Box::new(ai)
+        };
+        let mut game = Game::init(1);
+        Players::new(ai(), ai()).play(&mut game).unwrap();
+        game.history().to_vec()
+    };
+
+    assert_eq!(play(11), play(11));
+}
+
+#[test]
+fn remote_players_mirror_the_moves_of_the_other_end() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (server, _) = listener.accept().unwrap();
+
+    // The host plays the first side with an AI, the guest the second one
+    let mut host_game = Game::init(1);
+    let mut host = Players::new(
+        Box::new(AI::with_seed(1, 3)),
+        Box::new(RemotePlayer::new(server.try_clone().unwrap(), server)),
+    );
+    let mut guest_game = Game::init(1);
+    let mut guest = Players::new(
+        Box::new(RemotePlayer::new(client.try_clone().unwrap(), client)),
+        Box::new(Mcts::new(500, 3)),
+    );
+
+    while !host_game.is_over() || !guest_game.is_over() {
+        host.step(&mut host_game).unwrap();
+        guest.step(&mut guest_game).unwrap();
+    }
+
+    assert_eq!(host_game.history(), guest_game.history());
+    assert!(!host.allows_undo());
+}
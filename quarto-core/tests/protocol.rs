@@ -0,0 +1,116 @@
+use std::{
+    io::Cursor,
+    thread,
+    time::{Duration, Instant},
+};
+
+use quarto_core::{
+    notation,
+    protocol::{self, Command, EnginePlayer, Go, Reply},
+    Game, Move, PhaseKind, Piece, Player, Players, Position, AI,
+};
+
+// A light piece in hand, with three light pieces on the first row
+const LIGHT_ROW: &str = "position startpos moves LRTS a1 LRTH b1 LSSS c1 LSTS";
+
+#[test]
+fn commands_and_replies_read_back_their_display() {
+    let position = Command::parse(LIGHT_ROW).unwrap();
+    let commands = [
+        Command::Quarto,
+        position,
+        Command::Go(Go {
+            kind: PhaseKind::PlacePiece,
+            movetime: Some(Duration::from_millis(250)),
+            depth: Some(4),
+        }),
+        Command::Stop,
+    ];
+    for command in commands {
+        assert_eq!(Command::parse(&command.to_string()).unwrap(), command);
+    }
+
+    let replies = [
+        Reply::Id("quarto-core AI level 3".to_string()),
+        Reply::Info("depth 3 score 12".to_string()),
+        Reply::BestMove(Some(Move::Give(Piece::all()[5]))),
+        Reply::BestMove(None),
+    ];
+    for reply in replies {
+        assert_eq!(Reply::parse(&reply.to_string()).unwrap(), reply);
+    }
+
+    assert!(Command::parse("go sideways").is_err());
+    assert!(Command::parse("position startpos moves a1").is_err());
+}
+
+#[test]
+fn served_ai_answers_with_the_winning_placement() {
+    let input = format!(
+        "quarto\nisready\n{}\ngo place depth 1\ngo give\n",
+        LIGHT_ROW
+    );
+    let mut output = vec![];
+
+    protocol::serve(AI::init(1), "test", Cursor::new(input), &mut output).unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(&lines[..3], ["id name test", "quartook", "readyok"]);
+    assert!(lines.contains(&"bestmove d1"));
+    // No piece is to be given while one is in hand
+    assert_eq!(lines.last(), Some(&"bestmove none"));
+}
+
+#[test]
+fn engine_process_plays_a_whole_game() {
+    let engine = EnginePlayer::spawn(
+        env!("CARGO_BIN_EXE_engine"),
+        &["--level", "1", "--threads", "1"],
+    )
+    .unwrap();
+    let mut game = Game::init(1);
+    let mut players = Players::new(Box::new(engine), Box::new(AI::with_seed(1, 3)));
+
+    while !game.is_over() {
+        if players.step(&mut game).unwrap().is_none() {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
+
+#[test]
+fn engine_answers_after_a_stale_move_are_kept() {
+    let mut engine = EnginePlayer::spawn(
+        env!("CARGO_BIN_EXE_engine"),
+        &["--level", "1", "--threads", "1"],
+    )
+    .unwrap();
+
+    // The answer for the first position comes back after the game moved on
+    assert_eq!(engine.choose_piece(&Position::initial()), None);
+    thread::sleep(Duration::from_millis(500));
+
+    let position = Game::from_moves(1, &notation::parse_moves("LRTS").unwrap())
+        .unwrap()
+        .position();
+    let deadline = Instant::now() + Duration::from_secs(30);
+    let coor = loop {
+        if let Some(coor) = engine.choose_placement(&position) {
+            break coor;
+        }
+        assert!(Instant::now() < deadline, "the engine never answered");
+        thread::sleep(Duration::from_millis(1));
+    };
+    assert!(position.board.get(coor).is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn engines_that_do_not_quit_are_killed() {
+    let engine = EnginePlayer::spawn("sleep", &["60"]).unwrap();
+
+    let started = Instant::now();
+    drop(engine);
+    assert!(started.elapsed() < Duration::from_secs(30));
+}
@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+use quarto_core::{
+    notation,
+    replay::{Replay, AUTO_PLAY_INTERVALS},
+    Coordinate, Game, Piece,
+};
+
+fn replay() -> Replay {
+    let moves = notation::parse_moves("LRTS c3:LRTS DSSH a1:DSSH LSTH").unwrap();
+    Replay::new(&Game::from_moves(1, &moves).unwrap()).unwrap()
+}
+
+fn piece(name: &str) -> Piece {
+    Piece::from_name(name).unwrap()
+}
+
+#[test]
+fn replays_step_within_the_game() {
+    let mut replay = replay();
+    assert_eq!((replay.move_number(), replay.moves_num()), (0, 5));
+
+    // Nothing comes before the start
+    assert!(!replay.step_back());
+    assert!(!replay.rewind());
+    assert_eq!(replay.move_number(), 0);
+
+    assert!(replay.step_forward());
+    assert!(replay.step_forward());
+    assert_eq!(replay.move_number(), 2);
+    assert!(replay.step_back());
+    assert_eq!(replay.move_number(), 1);
+
+    assert!(replay.fast_forward());
+    assert_eq!(replay.move_number(), 5);
+    // Nor after the end
+    assert!(!replay.step_forward());
+    assert!(!replay.fast_forward());
+    assert_eq!(replay.move_number(), 5);
+
+    assert!(replay.rewind());
+    assert_eq!(replay.game.position(), Game::init(1).position());
+}
+
+#[test]
+fn auto_play_speed_stays_in_range() {
+    let mut replay = replay();
+    assert_eq!(replay.interval(), AUTO_PLAY_INTERVALS[1]);
+
+    for _ in 0..10 {
+        replay.slower();
+    }
+    assert_eq!(replay.interval(), AUTO_PLAY_INTERVALS[0]);
+
+    for _ in 0..10 {
+        replay.faster();
+    }
+    assert_eq!(
+        replay.interval(),
+        AUTO_PLAY_INTERVALS[AUTO_PLAY_INTERVALS.len() - 1]
+    );
+}
+
+#[test]
+fn auto_play_steps_once_per_interval_until_the_end() {
+    let mut replay = replay();
+    let interval = replay.interval();
+
+    // Only once started
+    assert!(!replay.update(interval));
+    replay.toggle_auto_play();
+
+    assert!(!replay.update(interval / 2));
+    assert!(replay.update(interval / 2));
+    assert_eq!(replay.move_number(), 1);
+
+    for _ in 1..5 {
+        assert!(replay.update(interval));
+    }
+    assert_eq!(replay.move_number(), 5);
+    assert!(!replay.update(interval));
+    assert!(!replay.auto_play);
+    assert!(!replay.update(Duration::from_secs(60)));
+}
+
+#[test]
+fn replays_describe_the_move_and_the_phase() {
+    let mut replay = replay();
+    assert_eq!(
+        replay.describe(),
+        "Replay 0/5 (start): Player to choose a piece"
+    );
+    assert_eq!(replay.highlight(), (piece("Light_Round_Tall_Solid"), None));
+
+    replay.step_forward();
+    replay.step_forward();
+    assert_eq!(
+        replay.describe(),
+        "Replay 2/5 (c3:LRTS): Opponent to choose a piece"
+    );
+    assert_eq!(
+        replay.highlight(),
+        (
+            piece("Dark_Square_Short_Hollow"),
+            Some(Coordinate::new(2, 2))
+        )
+    );
+
+    replay.toggle_auto_play();
+    assert!(replay
+        .describe()
+        .ends_with(&format!("[auto-play {} ms]", replay.interval().as_millis())));
+}
@@ -0,0 +1,99 @@
+use quarto_core::{
+    notation,
+    save::{self, SaveError},
+    Game, Position,
+};
+
+fn played_game() -> Game {
+    let moves = notation::parse_moves("LRTS c3:LRTS DSSH a1:DSSH LSTH d4:LSTH DRTS").unwrap();
+    let mut game = Game::from_moves(2, &moves).unwrap();
+    game.undo();
+    game.undo();
+    game
+}
+
+#[test]
+fn games_round_trip_with_their_undone_moves() {
+    let game = played_game();
+    let text = save::write_game(&game);
+    let mut loaded = save::read_game(&text).unwrap();
+
+    assert_eq!(loaded.level, 2);
+    assert_eq!(loaded.position(), game.position());
+    assert_eq!(loaded.history(), game.history());
+    assert_eq!(loaded.redo_moves(), game.redo_moves());
+    assert_eq!(save::write_game(&loaded), text);
+
+    while loaded.redo().is_some() {}
+    assert_eq!(loaded.history().len(), 7);
+}
+
+#[test]
+fn games_from_another_position_round_trip() {
+    let start = played_game().position();
+    let moves = notation::parse_moves("d4:LSTH DRTS b2:DRTS LRSH").unwrap();
+    let mut game = Game::from_position_and_moves(3, start.clone(), &moves).unwrap();
+    game.undo();
+
+    let loaded = save::read_game(&save::write_game(&game)).unwrap();
+    assert_eq!(loaded.start(), &start);
+    assert_eq!(loaded.position(), game.position());
+    assert_eq!(loaded.history(), game.history());
+    assert_eq!(loaded.redo_moves(), game.redo_moves());
+}
+
+#[test]
+fn version_1_games_start_from_the_initial_position() {
+    let game = played_game();
+    let text = save::write_game(&game)
+        .replacen(
+            &format!("quarto-save {}", save::SAVE_FORMAT_VERSION),
+            "quarto-save 1",
+            1,
+        )
+        .replacen(&format!("start {}\n", Position::initial()), "", 1);
+
+    let loaded = save::read_game(&text).unwrap();
+    assert_eq!(loaded.start(), &Position::initial());
+    assert_eq!(loaded.position(), game.position());
+}
+
+#[test]
+fn other_versions_are_rejected() {
+    let text = save::write_game(&played_game()).replacen(
+        &format!("quarto-save {}", save::SAVE_FORMAT_VERSION),
+        "quarto-save 99",
+        1,
+    );
+
+    assert!(matches!(
+        save::read_game(&text),
+        Err(SaveError::UnsupportedVersion(99))
+    ));
+    assert!(matches!(
+        save::read_game("quarto-game 1\n"),
+        Err(SaveError::Malformed(_))
+    ));
+}
+
+#[test]
+fn moves_must_lead_to_the_recorded_position() {
+    let text = save::write_game(&played_game());
+
+    // One move fewer taken back than the position was written for
+    let undone_once = text.replace("undone 2", "undone 1");
+    assert!(matches!(
+        save::read_game(&undone_once),
+        Err(SaveError::PositionMismatch { .. })
+    ));
+
+    let too_many_undone = text.replace("undone 2", "undone 8");
+    assert!(matches!(
+        save::read_game(&too_many_undone),
+        Err(SaveError::Malformed(_))
+    ));
+
+    // Handing over a piece already on the board
+    let illegal = text.replace("LSTH d4:LSTH", "LRTS d4:LRTS");
+    assert!(matches!(save::read_game(&illegal), Err(SaveError::Game(_))));
+}
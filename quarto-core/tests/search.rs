@@ -0,0 +1,177 @@
+use std::{
+    sync::{atomic::AtomicBool, Arc},
+    time::{Duration, Instant},
+};
+
+use quarto_core::{search::WIN_SCORE, CompoundMove, Coordinate, Game, Players, Search, AI};
+
+mod common;
+
+use common::{drawn_board, position};
+
+// Three light pieces on the first row, with a fourth one in hand
+const LIGHT_ROW: &str = "LRTS a1:LRTS LRTH b1:LRTH LSSS c1:LSSS LSTS";
+
+#[test]
+fn immediate_wins_are_taken() {
+    let position = position(LIGHT_ROW);
+
+    for depth in 1..=3 {
+        let result = Search::default().run(&position, depth);
+        let win = CompoundMove {
+            place: Some(Coordinate::new(0, 3)),
+            give: None,
+        };
+        assert_eq!(result.best, win);
+        assert_eq!(result.score, WIN_SCORE);
+        assert_eq!(result.pv, vec![win]);
+    }
+}
+
+#[test]
+fn poisoned_pieces_are_kept_while_a_safe_one_is_left() {
+    // Any light piece completes the first row, the dark ones do not
+    let position = position("LRTS a1:LRTS LRTH b1:LRTH LSSS c1:LSSS");
+
+    for depth in 1..=3 {
+        let result = Search::default().run(&position, depth);
+        let give = result.best.give.unwrap();
+        assert_eq!(result.best.place, None);
+        assert!(give.name().starts_with("Dark"), "{} gives {}", depth, give);
+        assert!(result.score > -WIN_SCORE + 1);
+    }
+}
+
+#[test]
+fn losses_score_by_their_distance() {
+    // Each of the four pieces left completes a column or a diagonal: the other
+    // side wins with its first compound move
+    let position = drawn_board(12, None);
+
+    for depth in 1..=3 {
+        let result = Search::default().run(&position, depth);
+        assert_eq!(result.score, -(WIN_SCORE - 1));
+        assert_eq!(result.pv.len(), 2);
+        assert_eq!(result.pv[1].give, None);
+    }
+}
+
+#[test]
+fn searches_stop_at_their_depth() {
+    let position = position("LRTS c3:LRTS DSSH a1:DSSH LSTH");
+
+    let mut nodes = 0;
+    for depth in 1..=3 {
+        let result = Search::default().run(&position, depth);
+        assert_eq!(result.depth, depth);
+        assert!(result.pv.len() <= depth as usize, "{:?}", result.pv);
+        assert!(result.nodes > nodes);
+        nodes = result.nodes;
+    }
+
+    // One compound move ahead, each move is scored by the evaluation after it
+    let depth_1 = Search::default().run(&position, 1);
+    assert_eq!(depth_1.pv, vec![depth_1.best]);
+}
+
+#[test]
+fn table_hits_are_counted_per_search() {
+    let position = position("LRTS c3:LRTS DSSH a1:DSSH LSTH");
+    let mut search = Search::default();
+
+    search.run(&position, 3);
+    let first = search.tt_hits();
+    assert!(first > 0);
+
+    // The table keeps its entries, so the same search finds more of them
+    search.run(&position, 3);
+    assert!(search.tt_hits() > first, "{} then {}", first, search.tt_hits());
+
+    // Each thread counts its own hits, added up when the search ends
+    search.set_threads(2);
+    search.run(&position, 3);
+    assert!(search.tt_hits() > 0);
+}
+
+#[test]
+fn parallel_search_agrees_with_the_sequential_one() {
+    let mut game = Game::init(1);
+    let mut players = Players::new(Box::new(AI::with_seed(1, 5)), Box::new(AI::with_seed(1, 9)));
+    let mut positions = vec![];
+    while !game.is_over() {
+        positions.push(game.position());
+        players.step(&mut game).unwrap();
+    }
+
+    for position in positions.iter().skip(6).step_by(3) {
+        let depth = if position.available_pieces.len() > 10 {
+            3
+        } else {
+            5
+        };
+
+        let sequential = Search::default().run(position, depth);
+        let mut search = Search::default();
+        search.set_threads(4);
+        let parallel = search.run(position, depth);
+
+        assert_eq!(parallel.score, sequential.score, "{}", position);
+    }
+}
+
+#[test]
+fn searches_without_moves_return_no_move() {
+    // A drawn game: the board is full and no piece is left to give
+    let position = drawn_board(16, None);
+    let no_move = CompoundMove {
+        place: None,
+        give: None,
+    };
+
+    assert_eq!(Search::default().run(&position, 2).best, no_move);
+
+    let mut search = Search::default();
+    search.set_stop_flag(Arc::new(AtomicBool::new(true)));
+    assert_eq!(
+        search.run_for(&position, Duration::from_millis(10), 2).best,
+        no_move
+    );
+}
+
+#[test]
+fn timed_scoring_goes_as_deep_as_the_budget_allows() {
+    let position = position("LRTS c3:LRTS DSSH a1:DSSH LSTH d4:LSTH DRTS b2:DRTS LRSH");
+
+    // The first depth completes however short the budget
+    let (scores, depth) = Search::default().score_moves_for(&position, Duration::ZERO, 3);
+    assert_eq!(depth, 1);
+    assert_eq!(scores, Search::default().score_moves(&position, 1));
+
+    let (scores, depth) = Search::default().score_moves_for(&position, Duration::from_secs(600), 2);
+    assert_eq!(depth, 2);
+    assert_eq!(scores, Search::default().score_moves(&position, 2));
+}
+
+#[test]
+fn timed_searches_keep_the_last_completed_depth() {
+    let position = position("LRTS c3:LRTS DSSH");
+    let budget = Duration::from_millis(100);
+
+    let started = Instant::now();
+    let result = Search::default().run_for(&position, budget, 16);
+    let elapsed = started.elapsed();
+
+    // The deadline passes in the middle of a depth, which is dropped
+    assert!(elapsed < budget + Duration::from_secs(2), "{:?}", elapsed);
+    assert!((1..16).contains(&result.depth), "depth {}", result.depth);
+    assert!(result.nodes > 0);
+    assert_eq!(result.pv.first(), Some(&result.best));
+
+    let place = result.best.place.unwrap();
+    let give = result.best.give.unwrap();
+    assert!(position.board.get(place).is_none());
+    assert!(position.available_pieces.contains(&give));
+
+    let completed = Search::default().run(&position, result.depth);
+    assert_eq!(result.score, completed.score);
+}
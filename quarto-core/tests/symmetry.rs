@@ -0,0 +1,101 @@
+use quarto_core::{
+    board::BOARD_CELLS_NUM,
+    symmetry::{self, BOARD_SYMMETRIES},
+    Board, Coordinate, Line, Piece, Position,
+};
+
+mod common;
+
+use common::position;
+
+fn sample() -> Position {
+    position("LRTS c3:LRTS DSSH a1:DSSH LSTH d4:LSTH DRTS b2:DRTS LRSH")
+}
+
+fn board_from(cell: impl Fn(usize) -> Option<Piece>) -> Board {
+    let mut mapped = Board::new();
+    for index in 0..BOARD_CELLS_NUM {
+        mapped.set(Coordinate::from_index(index), cell(index));
+    }
+    mapped
+}
+
+// Piece whose attribute `to[n]` has the value of the attribute `n` of `piece`,
+// with the attributes of `inversion` inverted
+fn relabel(piece: Piece, to: [u8; 4], inversion: u8) -> Piece {
+    let bits = (0..4)
+        .filter(|n| piece.bits() & 1 << n != 0)
+        .fold(0, |bits, n| bits | 1 << to[n as usize]);
+    Piece::from_bits(bits ^ inversion)
+}
+
+#[test]
+fn board_symmetries_keep_lines_and_the_hash() {
+    let position = sample();
+    let hash = position.canonical_hash();
+    let lines = Line::all().map(|line| {
+        let mut cells = line.coords().map(Coordinate::index);
+        cells.sort_unstable();
+        cells
+    });
+
+    for symmetry in BOARD_SYMMETRIES {
+        for line in lines {
+            let mut image = line.map(|index| symmetry[index]);
+            image.sort_unstable();
+            assert!(lines.contains(&image), "{:?} is not a line", image);
+        }
+
+        let board = board_from(|index| position.board.get(Coordinate::from_index(symmetry[index])));
+        assert_eq!(
+            symmetry::canonical_hash(&board, position.piece_in_hand()),
+            hash
+        );
+    }
+}
+
+#[test]
+fn attribute_relabellings_keep_the_hash() {
+    let position = sample();
+    let hash = position.canonical_hash();
+
+    let mut relabellings = 0;
+    for code in 0..256u16 {
+        let to = [0, 2, 4, 6].map(|shift| (code >> shift & 0b11) as u8);
+        if (1..4).any(|n| to[..n].contains(&to[n])) {
+            continue;
+        }
+        relabellings += 1;
+
+        for inversion in 0..16 {
+            let board = board_from(|index| {
+                position
+                    .board
+                    .get(Coordinate::from_index(index))
+                    .map(|piece| relabel(piece, to, inversion))
+            });
+            let piece_in_hand = position
+                .piece_in_hand()
+                .map(|piece| relabel(piece, to, inversion));
+
+            assert_eq!(symmetry::canonical_hash(&board, piece_in_hand), hash);
+        }
+    }
+    assert_eq!(relabellings, 24);
+}
+
+#[test]
+fn different_positions_hash_differently() {
+    // The same two pieces sharing a row, or no line at all
+    let in_line = position("LRTS a1 DSSH b1");
+    let apart = position("LRTS a1 DSSH b3");
+    // Two pieces sharing three attributes, or none
+    let alike = position("LRTS a1 LRTH b1");
+    // The same board, with or without a piece in hand
+    let in_hand = position("LRTS a1 DSSH b1 LRTH");
+
+    let hashes = [&in_line, &apart, &alike, &in_hand].map(|each| each.canonical_hash());
+    for (n, hash) in hashes.iter().enumerate() {
+        assert!(!hashes[..n].contains(hash), "{}", n);
+    }
+}
@@ -0,0 +1,44 @@
+use quarto_core::transposition::{Bound, TranspositionTable};
+
+#[test]
+fn stored_entries_are_probed_back() {
+    let table = TranspositionTable::new(1);
+    let hash = 0x1234_5678_9abc_def0;
+
+    assert_eq!(table.probe(hash), None);
+    table.store(hash, -42, 3, Bound::Lower);
+
+    let entry = table.probe(hash).unwrap();
+    assert_eq!(
+        (entry.hash, entry.score, entry.depth, entry.bound),
+        (hash, -42, 3, Bound::Lower)
+    );
+
+    // Another position in the same slot is not mistaken for this one
+    assert_eq!(table.probe(hash ^ (table.len() as u64) << 4), None);
+
+    table.clear();
+    assert_eq!(table.probe(hash), None);
+}
+
+#[test]
+fn deeper_or_newer_entries_replace_older_ones() {
+    let table = TranspositionTable::new(1);
+    let hash = 7;
+    // Same slot, another position
+    let other = hash + table.len() as u64;
+
+    table.store(hash, 10, 4, Bound::Exact);
+    table.store(other, 20, 2, Bound::Exact);
+    assert_eq!(table.probe(hash).unwrap().score, 10);
+    assert_eq!(table.probe(other), None);
+
+    table.store(other, 30, 4, Bound::Upper);
+    assert_eq!(table.probe(hash), None);
+    assert_eq!(table.probe(other).unwrap().score, 30);
+
+    // Results of an earlier search give way even to shallower ones
+    table.new_search();
+    table.store(hash, 40, 1, Bound::Exact);
+    assert_eq!(table.probe(hash).unwrap().score, 40);
+}
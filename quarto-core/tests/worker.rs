@@ -0,0 +1,118 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use quarto_core::{
+    worker::{Handshake, Worker},
+    Coordinate, Piece, Player, Position,
+};
+
+mod common;
+
+use common::position;
+
+/// Engine that thinks for `duration` unless stopped, then gives the first
+/// piece left. Each of its searches is logged as completed or stopped.
+struct SlowEngine {
+    duration: Duration,
+    stop: Arc<AtomicBool>,
+    completed: Arc<Mutex<Vec<bool>>>,
+}
+
+impl Player for SlowEngine {
+    fn choose_placement(&mut self, position: &Position) -> Option<Coordinate> {
+        position.board.free_coords().next()
+    }
+
+    fn choose_piece(&mut self, position: &Position) -> Option<Piece> {
+        let started = Instant::now();
+        while started.elapsed() < self.duration && !self.stop.load(Ordering::Relaxed) {
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        let completed = !self.stop.load(Ordering::Relaxed);
+        self.completed.lock().unwrap().push(completed);
+        position.available_pieces.first().copied()
+    }
+}
+
+fn worker(duration: Duration) -> (Worker, Arc<Mutex<Vec<bool>>>) {
+    let stop = Arc::new(AtomicBool::new(false));
+    let completed = Arc::new(Mutex::new(vec![]));
+    let engine = SlowEngine {
+        duration,
+        stop: stop.clone(),
+        completed: completed.clone(),
+    };
+    (Worker::spawn(Box::new(engine), stop), completed)
+}
+
+fn wait_for_piece(worker: &mut Worker, position: &Position) -> Piece {
+    let deadline = Instant::now() + Duration::from_secs(30);
+    loop {
+        if let Some(piece) = worker.choose_piece(position) {
+            return piece;
+        }
+        assert!(Instant::now() < deadline, "the worker never answered");
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn answers_to_cancelled_jobs_are_dropped() {
+    let (mut worker, _) = worker(Duration::ZERO);
+    let first = Position::initial();
+    // The piece the engine gives first is on the board here
+    let second = position("LRTS c3:LRTS DSSH a1:DSSH");
+
+    assert_eq!(worker.choose_piece(&first), None);
+    assert!(worker.is_thinking());
+    thread::sleep(Duration::from_millis(50));
+
+    // As after an undo: the answer for `first` comes back but is not wanted
+    worker.cancel();
+    assert!(!worker.is_thinking());
+    let piece = wait_for_piece(&mut worker, &second);
+    assert!(second.available_pieces.contains(&piece), "{}", piece);
+}
+
+#[test]
+fn jobs_after_a_cancelled_one_run_to_the_end() {
+    let (mut worker, completed) = worker(Duration::from_millis(100));
+    let first = Position::initial();
+    let second = position("LRTS c3:LRTS DSSH a1:DSSH");
+
+    assert_eq!(worker.choose_piece(&first), None);
+    thread::sleep(Duration::from_millis(20));
+    worker.cancel();
+
+    wait_for_piece(&mut worker, &second);
+    let completed = completed.lock().unwrap();
+    assert_eq!(completed.last(), Some(&true));
+    assert!(completed.len() <= 2);
+}
+
+#[test]
+fn cancelled_jobs_are_skipped_or_stopped() {
+    let stop = Arc::new(AtomicBool::new(false));
+    let handshake = Handshake::new(stop.clone());
+
+    // Cancelled before the worker thread takes it
+    handshake.cancel(1);
+    assert!(!handshake.start(1));
+
+    // Cancelled once started
+    assert!(handshake.start(2));
+    assert!(!stop.load(Ordering::SeqCst));
+    handshake.cancel(2);
+    assert!(handshake.is_stopped());
+
+    // The next job is not stopped by the earlier cancellation
+    assert!(handshake.start(3));
+    assert!(!handshake.is_stopped());
+}
@@ -1,13 +1,10 @@
-use std::collections::HashMap;
+use std::{cell::Cell, collections::HashMap, rc::Rc};
 
 use cgmath::prelude::*;
-use winit::event::{
-    ElementState, KeyboardInput,
-    MouseScrollDelta::{self, LineDelta, PixelDelta},
-    TouchPhase, VirtualKeyCode, WindowEvent,
-};
+use quarto_core::{Coordinate, Game, Move, Phase, Piece, Player, Players, Position};
+use winit::event::{ElementState, VirtualKeyCode};
 
-use crate::{ai::AI, instance, model::Model};
+use crate::{instance, model::Model};
 
 // pub const GAME_PIECES_NAMES: [&str; 16] = [
 //     "Light_Round_Tall_Hollow",
@@ -143,551 +140,288 @@ pub fn get_board_coords() -> [(Coordinate, [f32; 3]); BOARD_COORDS_NUM] {
     board_coords
 }
 
+/// Keyboard cursor: the piece the arrow points at and the cell the circle is on.
+/// The circle is only shown while a local player has a piece to place.
 #[derive(Debug, Copy, Clone)]
-pub enum Turn {
-    Player,
-    Opponent,
+pub struct Selection {
+    pub piece: Piece,
+    pub coor: Option<Coordinate>,
 }
 
-#[derive(Debug, Copy, Clone)]
-pub struct Coordinate {
-    pub row: i8,
-    pub col: i8,
-}
+impl Selection {
+    pub fn new(game: &Game, players: &Players) -> Self {
+        let mut selection = Self {
+            piece: game
+                .available_pieces
+                .first()
+                .copied()
+                .unwrap_or_else(|| Piece::all()[0]),
+            coor: None,
+        };
+        selection.sync(game, players);
+        selection
+    }
 
-#[derive(Debug, Copy, Clone)]
-pub struct Piece {
-    pub name: &'static str,
-    pub arrow_point: [f32; 3],
+    /// Keeps the cursor on something selectable after the game state changed.
+    pub fn sync(&mut self, game: &Game, players: &Players) {
+        match game.phase {
+            Phase::PlacePiece { by, piece } => {
+                self.piece = piece;
+                self.coor = match self.coor {
+                    _ if !players.get(by).is_local() => None,
+                    Some(coor) if game.board.is_free(coor) => Some(coor),
+                    _ => game.board.free_coords().next(),
+                };
+            }
+            Phase::ChoosePieceForOpponent { .. } => {
+                if !game.available_pieces.contains(&self.piece) {
+                    if let Some(piece) = game.available_pieces.first() {
+                        self.piece = *piece;
+                    }
+                }
+                self.coor = None;
+            }
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
-pub struct Game {
-    pub turn: Turn,
-    pub selected_piece: Piece,
-    pub selected_coor: Option<Coordinate>,
-    pub available_pieces: Vec<Piece>,
-    pub board: Vec<(Coordinate, Option<Piece>)>,
-    pub piece_played: bool,
-    pub ai: AI,
-    pub ended: bool,
-}
+/// Writes the instance buffers of the pieces so that they match the board of
+/// `game`.
+pub fn update_pieces(
+    game: &Game,
+    queue: &wgpu::Queue,
+    game_pieces: &HashMap<&'static str, (Model, wgpu::Buffer, [f32; 3])>,
+    game_piece_initial_instance_data: &instance::InstanceRaw,
+    board_coords: &[(Coordinate, [f32; 3])],
+) {
+    for (name, (_, instance_buffer, board_point)) in game_pieces.iter() {
+        let placed_coor = game
+            .board
+            .iter()
+            .find(|(_, cell)| matches!(cell, Some(piece) if piece.name() == *name))
+            .map(|(coor, _)| coor);
+
+        let game_piece_instance_data = match placed_coor {
+            Some(coor) => {
+                let (_, circle_point) = board_coords
+                    .iter()
+                    .find(|each| each.0 == coor)
+                    .unwrap();
+
+                let new_point = [
+                    board_point[0] + circle_point[0],
+                    board_point[1] + circle_point[1],
+                    board_point[2] + circle_point[2],
+                ];
+
+                let game_piece_pos = cgmath::Vector3::from(new_point);
 
-impl Game {
-    pub fn init(level: usize) -> Self {
-        let available_pieces = GAME_PIECES_NAMES
-            .map(|p| Piece {
-                name: p.0,
-                arrow_point: p.1,
-            })
-            .to_vec();
-
-        let mut board = Vec::<(Coordinate, Option<Piece>)>::new();
-        for row in 0..4 {
-            for col in 0..4 {
-                board.push((Coordinate { row, col }, None));
+                let game_piece_rot = if game_piece_pos.is_zero() {
+                    cgmath::Quaternion::from_axis_angle(cgmath::Vector3::unit_z(), cgmath::Deg(0.0))
+                } else {
+                    cgmath::Quaternion::from_axis_angle(
+                        game_piece_pos.normalize(),
+                        cgmath::Deg(0.0),
+                    )
+                };
+
+                instance::Instance {
+                    position: game_piece_pos,
+                    rotation: game_piece_rot,
+                }
+                .to_raw()
             }
-        }
+            None => *game_piece_initial_instance_data,
+        };
 
-        let ai = AI::init(level);
-
-        Self {
-            turn: Turn::Player,
-            selected_piece: available_pieces[0],
-            selected_coor: None,
-            available_pieces,
-            board,
-            piece_played: false,
-            ai,
-            ended: false,
-        }
+        queue.write_buffer(
+            instance_buffer,
+            0,
+            bytemuck::cast_slice(&[game_piece_instance_data]),
+        );
     }
+}
 
-    pub fn reset(&mut self, level: usize) {
-        *self = Game::init(level);
+/// Writes the instance buffers of the arrow and the circle so that they point
+/// at `selection`.
+pub fn update_selection(
+    selection: &Selection,
+    queue: &wgpu::Queue,
+    arrow_instances_data: &HashMap<&'static str, instance::InstanceRaw>,
+    arrow_instance_buffer: &wgpu::Buffer,
+    circle_instances_data: &HashMap<(i8, i8), instance::InstanceRaw>,
+    circle_instance_buffer: &wgpu::Buffer,
+) {
+    if let Some(coor) = selection.coor {
+        queue.write_buffer(
+            circle_instance_buffer,
+            0,
+            bytemuck::cast_slice(&[*circle_instances_data.get(&(coor.row, coor.col)).unwrap()]),
+        );
     }
 
-    pub fn has_same_feature(&self, board_filtered: &Vec<&(Coordinate, Option<Piece>)>) -> bool {
-        let mut same_color = false;
-        let mut same_shape = false;
-        let mut same_height = false;
-        let mut same_state = false;
-
-        let piece_0: Vec<_> = board_filtered[0].1.unwrap().name.split("_").collect();
-        let piece_1: Vec<_> = board_filtered[1].1.unwrap().name.split("_").collect();
-        let piece_2: Vec<_> = board_filtered[2].1.unwrap().name.split("_").collect();
-        let piece_3: Vec<_> = board_filtered[3].1.unwrap().name.split("_").collect();
-
-        if piece_0[0] == piece_1[0] && piece_1[0] == piece_2[0] && piece_2[0] == piece_3[0] {
-            same_color = true;
-        } else if piece_0[1] == piece_1[1] && piece_1[1] == piece_2[1] && piece_2[1] == piece_3[1] {
-            same_shape = true;
-        } else if piece_0[2] == piece_1[2] && piece_1[2] == piece_2[2] && piece_2[2] == piece_3[2] {
-            same_height = true;
-        } else if piece_0[3] == piece_1[3] && piece_1[3] == piece_2[3] && piece_2[3] == piece_3[3] {
-            same_state = true;
-        }
+    queue.write_buffer(
+        arrow_instance_buffer,
+        0,
+        bytemuck::cast_slice(&[*arrow_instances_data.get(selection.piece.name()).unwrap()]),
+    );
+}
 
-        same_color || same_shape || same_height || same_state
+/// Moves the board cursor between empty cells: to the neighbouring cell in the
+/// direction of the key when it is empty, otherwise to the previous or next empty cell.
+fn move_coor(game: &Game, coor: Coordinate, key: VirtualKeyCode) -> Coordinate {
+    let available_coors = game.board.free_coords().collect::<Vec<_>>();
+
+    let (d_row, d_col, forward) = match key {
+        VirtualKeyCode::Up => (-1, 0, false),
+        VirtualKeyCode::Down => (1, 0, true),
+        VirtualKeyCode::Left => (0, -1, false),
+        _ => (0, 1, true),
+    };
+    let neighbour = Coordinate::new(coor.row + d_row, coor.col + d_col);
+
+    if available_coors.contains(&neighbour) {
+        return neighbour;
     }
 
-    pub fn check_row(&self, n: i8) -> bool {
-        let board_filtered = self
-            .board
-            .iter()
-            .filter(|each| each.0.row == n && each.1.is_some())
-            .collect::<Vec<_>>();
-
-        if board_filtered.len() < 4 {
-            return false;
+    match available_coors.iter().position(|each| *each == coor) {
+        Some(coor_index) => {
+            let num_coors = available_coors.len();
+            let coor_index = if forward {
+                (coor_index + 1) % num_coors
+            } else {
+                (coor_index + num_coors - 1) % num_coors
+            };
+            available_coors[coor_index]
         }
+        None => coor,
+    }
+}
 
-        self.has_same_feature(&board_filtered)
+/// Cycles the arrow through the available pieces.
+fn move_piece(game: &Game, piece: Piece, forward: bool) -> Piece {
+    match game.available_pieces.iter().position(|each| *each == piece) {
+        Some(index) => {
+            let num_pieces = game.available_pieces.len();
+            let index = if forward {
+                (index + 1) % num_pieces
+            } else {
+                (index + num_pieces - 1) % num_pieces
+            };
+            game.available_pieces[index]
+        }
+        None => piece,
     }
+}
 
-    pub fn check_col(&self, n: i8) -> bool {
-        let board_filtered = self
-            .board
-            .iter()
-            .filter(|each| each.0.col == n && each.1.is_some())
-            .collect::<Vec<_>>();
+/// Local player, whose moves are confirmed with Return. Clones share the
+/// confirmed move, so both sides of a game at one keyboard can be clones.
+#[derive(Debug, Clone, Default)]
+pub struct Keyboard {
+    confirmed: Rc<Cell<Option<Move>>>,
+}
 
-        if board_filtered.len() < 4 {
-            return false;
-        }
+impl Keyboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-        self.has_same_feature(&board_filtered)
+    fn confirm(&self, mv: Move) {
+        self.confirmed.set(Some(mv));
     }
+}
 
-    pub fn check_diagonal_lt_rb(&self) -> bool {
-        let board_filtered = self
-            .board
-            .iter()
-            .filter(|each| {
-                (each.0.row == 0 && each.0.col == 0
-                    || each.0.row == 1 && each.0.col == 1
-                    || each.0.row == 2 && each.0.col == 2
-                    || each.0.row == 3 && each.0.col == 3)
-                    && each.1.is_some()
-            })
-            .collect::<Vec<_>>();
-
-        if board_filtered.len() < 4 {
-            return false;
+impl Player for Keyboard {
+    fn choose_placement(&mut self, _position: &Position) -> Option<Coordinate> {
+        match self.confirmed.take()? {
+            Move::Place(coor) => Some(coor),
+            Move::Give(_) => None,
         }
-
-        self.has_same_feature(&board_filtered)
     }
 
-    pub fn check_diagonal_rt_lb(&self) -> bool {
-        let board_filtered = self
-            .board
-            .iter()
-            .filter(|each| {
-                (each.0.row == 0 && each.0.col == 3
-                    || each.0.row == 1 && each.0.col == 2
-                    || each.0.row == 2 && each.0.col == 1
-                    || each.0.row == 3 && each.0.col == 0)
-                    && each.1.is_some()
-            })
-            .collect::<Vec<_>>();
-
-        if board_filtered.len() < 4 {
-            return false;
+    fn choose_piece(&mut self, _position: &Position) -> Option<Piece> {
+        match self.confirmed.take()? {
+            Move::Give(piece) => Some(piece),
+            Move::Place(_) => None,
         }
+    }
 
-        self.has_same_feature(&board_filtered)
+    fn is_local(&self) -> bool {
+        true
     }
+}
 
-    pub fn check_game_state(&mut self) -> bool {
-        if self.check_diagonal_lt_rb() || self.check_diagonal_rt_lb() {
-            return true;
-        };
+pub fn process_keyboard(
+    game: &mut Game,
+    players: &mut Players,
+    keyboard: &Keyboard,
+    selection: &mut Selection,
+    key: VirtualKeyCode,
+    state: ElementState,
+) -> bool {
+    if state != ElementState::Pressed {
+        return false;
+    }
 
-        for n in 0..4 {
-            if self.check_row(n) {
-                return true;
-            };
+    //* Undo / redo a whole turn of the local player, together with the answer,
+    //* even while the answer is being thought about
+    if key == VirtualKeyCode::Z || key == VirtualKeyCode::Y {
+        let has_local = players.player.is_local() || players.opponent.is_local();
+        if !has_local || !players.allows_undo() {
+            return false;
         }
 
-        for n in 0..4 {
-            if self.check_col(n) {
-                return true;
+        players.cancel();
+        loop {
+            let stepped = if key == VirtualKeyCode::Z {
+                game.undo()
+            } else {
+                game.redo()
             };
+            if stepped.is_none() || players.get(game.turn()).is_local() {
+                break;
+            }
         }
+        selection.sync(game, players);
 
-        false
+        return true;
     }
 
-    pub fn update(
-        &mut self,
-        queue: &wgpu::Queue,
-        arrow_instances_data: &HashMap<&'static str, instance::InstanceRaw>,
-        arrow_instance_buffer: &wgpu::Buffer,
-        circle_instances_data: &HashMap<(i8, i8), instance::InstanceRaw>,
-        circle_instance_buffer: &wgpu::Buffer,
-        game_pieces: &HashMap<&'static str, (Model, wgpu::Buffer, [f32; 3])>,
-        board_coords: &Vec<(Coordinate, [f32; 3])>,
-    ) {
-        match self.selected_coor {
-            Some(coor) => {
-                if self.piece_played {
-                    match game_pieces.get(&self.selected_piece.name) {
-                        Some((_, instance_buffer, board_point)) => {
-                            let game_piece_pos;
-                            let game_piece_rot;
-
-                            let (_, circle_point) = board_coords
-                                .iter()
-                                .find(|each| each.0.row == coor.row && each.0.col == coor.col)
-                                .unwrap();
-
-                            let new_point = [
-                                board_point[0] + circle_point[0],
-                                board_point[1] + circle_point[1],
-                                board_point[2] + circle_point[2],
-                            ];
-
-                            game_piece_pos = cgmath::Vector3::from(new_point);
-
-                            game_piece_rot = if game_piece_pos.is_zero() {
-                                cgmath::Quaternion::from_axis_angle(
-                                    cgmath::Vector3::unit_z(),
-                                    cgmath::Deg(0.0),
-                                )
-                            } else {
-                                cgmath::Quaternion::from_axis_angle(
-                                    game_piece_pos.normalize(),
-                                    cgmath::Deg(0.0),
-                                )
-                            };
-
-                            let game_piece_instance_data = instance::Instance {
-                                position: game_piece_pos,
-                                rotation: game_piece_rot,
-                            }
-                            .to_raw();
-
-                            queue.write_buffer(
-                                &instance_buffer,
-                                0,
-                                bytemuck::cast_slice(&[game_piece_instance_data]),
-                            );
-
-                            self.selected_coor = None;
-                        }
-                        None => {}
-                    }
-
-                    let index = self
-                        .available_pieces
-                        .iter()
-                        .position(|each| each.name == self.selected_piece.name)
-                        .unwrap();
-
-                    self.available_pieces.remove(index);
-
-                    if self.available_pieces.len() > 0 {
-                        self.selected_piece = self.available_pieces[0];
-                    }
-
-                    match self.turn {
-                        Turn::Opponent => {
-                            match self.board.iter().find(|each| each.1.is_none()) {
-                                Some(available_coor) => {
-                                    self.ai.select_piece(
-                                        &mut self.available_pieces,
-                                        &mut self.selected_piece,
-                                    );
-
-                                    self.selected_coor = Some(Coordinate {
-                                        row: available_coor.0.row,
-                                        col: available_coor.0.col,
-                                    });
-
-                                    queue.write_buffer(
-                                        &circle_instance_buffer,
-                                        0,
-                                        bytemuck::cast_slice(&[*circle_instances_data
-                                            .get(&(available_coor.0.row, available_coor.0.col))
-                                            .unwrap()]),
-                                    );
-                                }
-                                None => {}
-                            }
-                            if self.check_game_state() {
-                                println!("{:?} won !!!", self.turn);
-                                self.ended = true;
-                            }
-                            self.turn = Turn::Player;
-                        }
-                        Turn::Player => {}
-                    }
-
-                    self.piece_played = false;
-                } else {
-                    queue.write_buffer(
-                        &circle_instance_buffer,
-                        0,
-                        bytemuck::cast_slice(&[*circle_instances_data
-                            .get(&(coor.row, coor.col))
-                            .unwrap()]),
-                    );
-                }
-            }
-            None => {}
-        }
-        queue.write_buffer(
-            &arrow_instance_buffer,
-            0,
-            bytemuck::cast_slice(&[*arrow_instances_data.get(&self.selected_piece.name).unwrap()]),
-        );
+    if !players.get(game.turn()).is_local() {
+        return false;
     }
 
-    pub fn process_keyboard(&mut self, key: VirtualKeyCode, state: ElementState) -> bool {
-        if state == ElementState::Pressed {
-            match key {
-                VirtualKeyCode::Up => {
-                    match self.selected_coor {
-                        Some(coor) => {
-                            let mut availab_coors_iter =
-                                self.board.iter().filter(|each| each.1.is_none());
-
-                            let mut availab_coors_iter_clone = availab_coors_iter.to_owned();
-
-                            match availab_coors_iter
-                                .find(|each| each.0.row == coor.row - 1 && each.0.col == coor.col)
-                            {
-                                Some(availab_coor) => {
-                                    // println!("");
-                                    self.selected_coor = Some(availab_coor.0);
-                                }
-                                None => {
-                                    // println!("Not Available");
-
-                                    let mut coor_index = availab_coors_iter_clone
-                                        .to_owned()
-                                        .position(|s| s.0.row == coor.row && s.0.col == coor.col)
-                                        .unwrap();
-
-                                    let num_coors = availab_coors_iter_clone.to_owned().count();
-
-                                    coor_index = if coor_index == 0 {
-                                        num_coors - 1
-                                    } else {
-                                        coor_index - 1
-                                    };
-
-                                    self.selected_coor =
-                                        Some(availab_coors_iter_clone.nth(coor_index).unwrap().0);
-                                }
-                            }
-                        }
-                        None => {}
-                    }
-
-                    true
+    match key {
+        VirtualKeyCode::Up | VirtualKeyCode::Down | VirtualKeyCode::Left | VirtualKeyCode::Right => {
+            match (game.phase, selection.coor) {
+                (Phase::PlacePiece { .. }, Some(coor)) => {
+                    selection.coor = Some(move_coor(game, coor, key));
                 }
-                VirtualKeyCode::Right => {
-                    match self.selected_coor {
-                        Some(coor) => {
-                            let mut availab_coors_iter =
-                                self.board.iter().filter(|each| each.1.is_none());
-
-                            let mut availab_coors_iter_clone = availab_coors_iter.to_owned();
-
-                            match availab_coors_iter
-                                .find(|each| each.0.row == coor.row && each.0.col == coor.col + 1)
-                            {
-                                Some(availab_coor) => {
-                                    // println!("");
-                                    self.selected_coor = Some(availab_coor.0);
-                                }
-                                None => {
-                                    // println!("Not Available");
-
-                                    let mut coor_index = availab_coors_iter_clone
-                                        .to_owned()
-                                        .position(|s| s.0.row == coor.row && s.0.col == coor.col)
-                                        .unwrap();
-
-                                    let num_coors = availab_coors_iter_clone.to_owned().count();
-
-                                    coor_index = if coor_index + 1 == num_coors {
-                                        0
-                                    } else {
-                                        coor_index + 1
-                                    };
-
-                                    self.selected_coor =
-                                        Some(availab_coors_iter_clone.nth(coor_index).unwrap().0);
-                                }
-                            }
-                        }
-                        None => {
-                            // println!("");
-                            // println!("{:?}", self.available_pieces);
-                            // println!("{:?}", self.selected_piece);
-                            // println!("");
-                            if self.available_pieces.len() > 0 {
-                                let mut index = self
-                                    .available_pieces
-                                    .iter()
-                                    .position(|&s| s.name == self.selected_piece.name)
-                                    .unwrap();
-
-                                let num_pieces = self.available_pieces.len();
-                                index = if index + 1 == num_pieces {
-                                    0
-                                } else {
-                                    index + 1
-                                };
-
-                                self.selected_piece = self.available_pieces[index];
-                                println!("Selected Piece: {:?}", self.selected_piece);
-                            }
-                        }
-                    }
-
-                    true
+                (Phase::ChoosePieceForOpponent { .. }, _)
+                    if key == VirtualKeyCode::Left || key == VirtualKeyCode::Right =>
+                {
+                    selection.piece =
+                        move_piece(game, selection.piece, key == VirtualKeyCode::Right);
+                    log::info!("selected piece {}", selection.piece);
                 }
-                VirtualKeyCode::Down => {
-                    match self.selected_coor {
-                        Some(coor) => {
-                            let mut availab_coors_iter =
-                                self.board.iter().filter(|each| each.1.is_none());
-
-                            let mut availab_coors_iter_clone = availab_coors_iter.to_owned();
-
-                            match availab_coors_iter
-                                .find(|each| each.0.row == coor.row + 1 && each.0.col == coor.col)
-                            {
-                                Some(availab_coor) => {
-                                    // println!("");
-                                    self.selected_coor = Some(availab_coor.0);
-                                }
-                                None => {
-                                    // println!("Not Available");
-
-                                    let mut coor_index = availab_coors_iter_clone
-                                        .to_owned()
-                                        .position(|s| s.0.row == coor.row && s.0.col == coor.col)
-                                        .unwrap();
-
-                                    let num_coors = availab_coors_iter_clone.to_owned().count();
-
-                                    coor_index = if coor_index + 1 == num_coors {
-                                        0
-                                    } else {
-                                        coor_index + 1
-                                    };
-
-                                    self.selected_coor =
-                                        Some(availab_coors_iter_clone.nth(coor_index).unwrap().0);
-                                }
-                            }
-                        }
-                        None => {}
-                    }
-
-                    true
-                }
-                VirtualKeyCode::Left => {
-                    match self.selected_coor {
-                        Some(coor) => {
-                            let mut availab_coors_iter =
-                                self.board.iter().filter(|each| each.1.is_none());
-
-                            let mut availab_coors_iter_clone = availab_coors_iter.to_owned();
-
-                            match availab_coors_iter
-                                .find(|each| each.0.row == coor.row && each.0.col == coor.col - 1)
-                            {
-                                Some(availab_coor) => {
-                                    // println!("");
-                                    self.selected_coor = Some(availab_coor.0);
-                                }
-                                None => {
-                                    // println!("Not Available");
-
-                                    let mut coor_index = availab_coors_iter_clone
-                                        .to_owned()
-                                        .position(|s| s.0.row == coor.row && s.0.col == coor.col)
-                                        .unwrap();
-
-                                    let num_coors = availab_coors_iter_clone.to_owned().count();
-
-                                    coor_index = if coor_index == 0 {
-                                        num_coors - 1
-                                    } else {
-                                        coor_index - 1
-                                    };
-
-                                    self.selected_coor =
-                                        Some(availab_coors_iter_clone.nth(coor_index).unwrap().0);
-                                }
-                            }
-                        }
-                        None => {
-                            if self.available_pieces.len() > 0 {
-                                let mut index = self
-                                    .available_pieces
-                                    .iter()
-                                    .position(|&s| s.name == self.selected_piece.name)
-                                    .unwrap();
-
-                                let num_pieces = self.available_pieces.len();
-                                index = if index == 0 {
-                                    num_pieces - 1
-                                } else {
-                                    index - 1
-                                };
-
-                                self.selected_piece = self.available_pieces[index];
-                                println!("Selected Piece: {:?}", self.selected_piece);
-                            }
-                        }
-                    }
-
-                    true
-                }
-                VirtualKeyCode::Return => {
-                    match self.selected_coor {
-                        Some(coor) => {
-                            self.board
-                                .iter_mut()
-                                .find(|s| s.0.row == coor.row && s.0.col == coor.col)
-                                .unwrap()
-                                .1 = Some(self.selected_piece);
-
-                            self.piece_played = true;
-
-                            if self.check_game_state() {
-                                println!("{:?} won >_<", self.turn);
-                                self.ended = true;
-                            }
-                        }
-                        None => match self.turn {
-                            Turn::Player => {
-                                self.ai.select_place(
-                                    &mut self.board,
-                                    self.selected_piece,
-                                    &mut self.selected_coor,
-                                );
-                                self.piece_played = true;
-                                self.turn = Turn::Opponent;
-                            }
-                            Turn::Opponent => {}
-                        },
-                    }
+                _ => {}
+            }
 
-                    true
-                }
+            true
+        }
+        VirtualKeyCode::Return => {
+            let mv = match (game.phase, selection.coor) {
+                (Phase::PlacePiece { .. }, Some(coor)) => Move::Place(coor),
+                _ => Move::Give(selection.piece),
+            };
 
-                _ => false,
+            match game.validate(mv) {
+                Ok(()) => keyboard.confirm(mv),
+                Err(error) => eprintln!("{}", error),
             }
-        } else {
-            false
+
+            true
         }
+        _ => false,
     }
 }
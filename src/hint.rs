@@ -0,0 +1,158 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver},
+        Arc,
+    },
+    thread,
+};
+
+use quarto_core::{
+    analysis::{self, Hint},
+    notation,
+    transposition::SMALL_TABLE_SIZE_MB,
+    Position, Search,
+};
+
+use crate::instance;
+
+//* Height of the hint arrow and circle above the cursor ones, so both show at once
+const HINT_LIFT: f32 = 0.01;
+
+/// Hints for the local player, each worked out on a thread of its own. A hint
+/// is only shown while the game is still in the position it was asked for.
+#[derive(Default)]
+pub struct Hints {
+    requested: Option<Request>,
+    shown: Option<(Position, Hint)>,
+}
+
+struct Request {
+    position: Position,
+    receiver: Receiver<Option<Hint>>,
+    /// Stops the thread working out the hint once it is no longer wanted.
+    stop: Arc<AtomicBool>,
+}
+
+impl Hints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts working out a hint for `position`, unless it is already.
+    pub fn request(&mut self, position: Position) {
+        if matches!(&self.requested, Some(request) if request.position == position) {
+            return;
+        }
+        self.cancel();
+
+        let (sender, receiver) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let mut search = Search::new(SMALL_TABLE_SIZE_MB);
+        search.set_threads(rayon::current_num_threads());
+        search.set_stop_flag(stop.clone());
+        let thread_position = position.clone();
+        thread::spawn(move || {
+            let hint =
+                analysis::hint_with(&mut search, &thread_position, analysis::HINT_TIME_BUDGET);
+            // The receiver is gone when the hint was no longer wanted
+            let _ = sender.send(hint);
+        });
+        self.requested = Some(Request {
+            position,
+            receiver,
+            stop,
+        });
+    }
+
+    /// Takes the hint that was asked for, once it is worked out. Returns `true`
+    /// when a new hint is to be shown.
+    pub fn poll(&mut self) -> bool {
+        let hint = match &self.requested {
+            Some(request) => match request.receiver.try_recv() {
+                Ok(hint) => hint,
+                Err(mpsc::TryRecvError::Empty) => return false,
+                Err(mpsc::TryRecvError::Disconnected) => None,
+            },
+            None => return false,
+        };
+
+        let request = self.requested.take().expect("a hint was requested");
+        self.shown = hint.map(|hint| (request.position, hint));
+        self.shown.is_some()
+    }
+
+    /// Hint to show in `position`.
+    pub fn shown(&self, position: &Position) -> Option<&Hint> {
+        match &self.shown {
+            Some((shown, hint)) if shown == position => Some(hint),
+            _ => None,
+        }
+    }
+
+    pub fn is_thinking(&self) -> bool {
+        self.requested.is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.cancel();
+        self.shown = None;
+    }
+
+    // Stops working out the hint that was asked for, if any
+    fn cancel(&mut self) {
+        if let Some(request) = self.requested.take() {
+            request.stop.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// One line readout of `hint`, e.g. `Hint: place on c3, give DRSS (winning)`.
+pub fn describe(hint: &Hint) -> String {
+    let mut steps = vec![];
+    if let Some(coor) = hint.place {
+        steps.push(format!("place on {}", notation::format_coordinate(coor)));
+    }
+    if let Some(piece) = hint.give {
+        steps.push(format!("give {}", notation::format_piece(piece)));
+    }
+
+    format!(
+        "Hint: {} ({}{})",
+        steps.join(", "),
+        hint.assessment,
+        if hint.exact { ", proven" } else { "" }
+    )
+}
+
+/// Writes the instance buffers of the hint arrow and circle to point at the
+/// piece and the cell of `hint`.
+pub fn update(
+    hint: &Hint,
+    queue: &wgpu::Queue,
+    arrow_instances_data: &HashMap<&'static str, instance::InstanceRaw>,
+    arrow_instance_buffer: &wgpu::Buffer,
+    circle_instances_data: &HashMap<(i8, i8), instance::InstanceRaw>,
+    circle_instance_buffer: &wgpu::Buffer,
+) {
+    if let Some(coor) = hint.place {
+        let mut circle_instance_data = *circle_instances_data.get(&(coor.row, coor.col)).unwrap();
+        circle_instance_data.model[3][1] += HINT_LIFT;
+        queue.write_buffer(
+            circle_instance_buffer,
+            0,
+            bytemuck::cast_slice(&[circle_instance_data]),
+        );
+    }
+
+    if let Some(piece) = hint.give {
+        let mut arrow_instance_data = *arrow_instances_data.get(piece.name()).unwrap();
+        arrow_instance_data.model[3][1] += HINT_LIFT;
+        queue.write_buffer(
+            arrow_instance_buffer,
+            0,
+            bytemuck::cast_slice(&[arrow_instance_data]),
+        );
+    }
+}
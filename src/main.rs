@@ -1,7 +1,20 @@
-use std::{collections::HashMap, env, f32::consts, path::Path};
+use std::{
+    collections::HashMap,
+    env,
+    f32::consts,
+    io,
+    path::Path,
+    sync::{atomic::AtomicBool, Arc},
+};
 
 use cgmath::prelude::*;
-use game::Coordinate;
+use quarto_core::{
+    book::OpeningBook,
+    mcts::Mcts,
+    player::RemotePlayer,
+    protocol::EnginePlayer,
+    replay, save, worker, Coordinate, Difficulty, Game, GameOutcome, Player, Players, AI,
+};
 use wgpu::util::DeviceExt;
 use winit::{
     dpi::PhysicalSize,
@@ -15,15 +28,94 @@ use model::{Model, Vertex};
 
 mod camera;
 mod game;
+mod hint;
 mod instance;
 mod light;
 mod model;
+mod overlay;
 mod texture;
 
-mod ai;
-
 use crate::game::{get_board_coords, GAME_PIECES_NAMES};
 
+const SAVE_FILE_PATH: &str = "quarto.save";
+const OPENING_BOOK_PATH: &str = "assets/opening_book.txt";
+
+/// Who plays a side, as given to `--player` and `--opponent`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Seat {
+    Keyboard,
+    Ai,
+    Mcts,
+    /// Peer connecting to this address.
+    Host(String),
+    /// Peer hosting at this address.
+    Join(String),
+    /// Engine process started with this command line, speaking the protocol
+    /// of `quarto_core::protocol`.
+    Engine(String),
+}
+
+impl Seat {
+    /// Reads `keyboard`, `ai`, `mcts`, `host:<address>`, `join:<address>` or
+    /// `engine:<command line>`.
+    fn parse(text: &str) -> Option<Self> {
+        match text.split_once(':') {
+            Some(("host", address)) => Some(Seat::Host(address.to_string())),
+            Some(("join", address)) => Some(Seat::Join(address.to_string())),
+            Some(("engine", command)) if !command.trim().is_empty() => {
+                Some(Seat::Engine(command.trim().to_string()))
+            }
+            _ => match text {
+                "keyboard" => Some(Seat::Keyboard),
+                "ai" => Some(Seat::Ai),
+                "mcts" => Some(Seat::Mcts),
+                _ => None,
+            },
+        }
+    }
+
+    fn is_remote(&self) -> bool {
+        matches!(self, Seat::Host(_) | Seat::Join(_))
+    }
+
+    /// Player for the seat. Remote seats connect here, waiting for the peer
+    /// when hosting.
+    fn player(
+        &self,
+        level: usize,
+        book: &Option<Arc<OpeningBook>>,
+        keyboard: &game::Keyboard,
+    ) -> io::Result<Box<dyn Player>> {
+        Ok(match self {
+            Seat::Keyboard => Box::new(keyboard.clone()),
+            Seat::Ai => {
+                let stop = Arc::new(AtomicBool::new(false));
+                let mut ai = AI::init(level);
+                ai.set_threads(rayon::current_num_threads());
+                ai.book = book.clone();
+                ai.set_stop_flag(stop.clone());
+                Box::new(worker::Worker::spawn(Box::new(ai), stop))
+            }
+            Seat::Mcts => {
+                let stop = Arc::new(AtomicBool::new(false));
+                let mut mcts = Mcts::init(level);
+                mcts.set_stop_flag(stop.clone());
+                Box::new(worker::Worker::spawn(Box::new(mcts), stop))
+            }
+            Seat::Host(address) => {
+                log::info!("waiting for the remote player on {}", address);
+                Box::new(RemotePlayer::listen(address.as_str())?)
+            }
+            Seat::Join(address) => Box::new(RemotePlayer::connect(address.as_str())?),
+            Seat::Engine(command) => {
+                let mut words = command.split_whitespace();
+                let program = words.next().unwrap_or_default();
+                Box::new(EnginePlayer::spawn(program, &words.collect::<Vec<_>>())?)
+            }
+        })
+    }
+}
+
 //* Refer to model module
 // #[repr(C)]
 // #[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
@@ -107,6 +199,41 @@ use crate::game::{get_board_coords, GAME_PIECES_NAMES};
 //     }, // E
 // ];
 
+/// Difficulty chosen by a number key of the difficulty menu.
+fn difficulty_key(key: VirtualKeyCode) -> Option<Difficulty> {
+    let level = match key {
+        VirtualKeyCode::Key1 | VirtualKeyCode::Numpad1 => 1,
+        VirtualKeyCode::Key2 | VirtualKeyCode::Numpad2 => 2,
+        VirtualKeyCode::Key3 | VirtualKeyCode::Numpad3 => 3,
+        VirtualKeyCode::Key4 | VirtualKeyCode::Numpad4 => 4,
+        VirtualKeyCode::Key5 | VirtualKeyCode::Numpad5 => 5,
+        VirtualKeyCode::Key6 | VirtualKeyCode::Numpad6 => 6,
+        _ => return None,
+    };
+    Some(Difficulty::from_level(level))
+}
+
+/// The difficulty menu, e.g. `Difficulty: 1 Beginner, [2 Easy], ... (1-6)`,
+/// the level being played in brackets.
+fn difficulty_menu(game_level: usize) -> String {
+    let current = Difficulty::from_level(game_level);
+    let entries = Difficulty::ALL
+        .iter()
+        .map(|difficulty| {
+            if *difficulty == current {
+                format!("[{} {}]", difficulty.level(), difficulty)
+            } else {
+                format!("{} {}", difficulty.level(), difficulty)
+            }
+        })
+        .collect::<Vec<_>>();
+    format!(
+        "Difficulty: {} (1-{})",
+        entries.join(", "),
+        Difficulty::ALL.len()
+    )
+}
+
 // const INDICES: &[u16] = &[0, 1, 4, 1, 2, 4, 2, 3, 4, /* padding */ 0];
 
 fn create_render_pipeline(
@@ -179,6 +306,10 @@ struct State {
     clear_color: wgpu::Color,
     render_pipeline: wgpu::RenderPipeline,
     arrow_circle_render_pipeline: wgpu::RenderPipeline,
+    hint_render_pipeline: wgpu::RenderPipeline,
+    poisoned_render_pipeline: wgpu::RenderPipeline,
+    threat_render_pipeline: wgpu::RenderPipeline,
+    threat_cell_render_pipeline: wgpu::RenderPipeline,
     custom_render_pipeline: wgpu::RenderPipeline,
     light_render_pipeline: wgpu::RenderPipeline,
     // render_texture_pipeline: wgpu::RenderPipeline,
@@ -211,14 +342,27 @@ struct State {
     circle_model: Model,
     circle_instances_data: HashMap<(i8, i8), instance::InstanceRaw>,
     circle_instance_buffer: wgpu::Buffer,
-    game: game::Game,
+    hint_arrow_instance_buffer: wgpu::Buffer,
+    hint_circle_instance_buffer: wgpu::Buffer,
+    hints: hint::Hints,
+    threat_circle_instance_buffer: wgpu::Buffer,
+    overlay: overlay::Overlay,
+    game: Game,
+    players: Players,
+    seats: [Seat; 2],
+    keyboard: game::Keyboard,
+    book: Option<Arc<OpeningBook>>,
+    selection: game::Selection,
+    replay: Option<replay::Replay>,
     game_level: usize,
+    /// Whether the number keys choose the difficulty.
+    difficulty_menu: bool,
     custom_material: model::Material,
     mouse_pressed: bool,
 }
 
 impl State {
-    async fn new(window: &Window) -> Self {
+    async fn new(window: &Window, seats: [Seat; 2], game_level: usize) -> io::Result<Self> {
         let size = window.inner_size();
         // The instance is a handle to our GPU
         // BackendBit::PRIMARY => Vulkan + Metal + DX12 + Browser WebGPU
@@ -632,6 +776,38 @@ impl State {
             )
         };
 
+        // *Overlay Pipelines: the textured models, coloured by their own
+        // fragment entry point
+        let overlay_render_pipeline = |label, fragment_entry_point, with_cull_mode| {
+            let shader = wgpu::ShaderModuleDescriptor {
+                label: Some(label),
+                source: wgpu::ShaderSource::Wgsl(include_str!("texture.wgsl").into()),
+            };
+
+            create_render_pipeline(
+                &device,
+                &render_pipeline_layout,
+                config.format,
+                Some(texture::Texture::DEPTH_FORMAT),
+                &[
+                    model::ModelTextureVertex::desc(),
+                    instance::InstanceRaw::desc(),
+                ],
+                shader,
+                "vs_main",
+                fragment_entry_point,
+                with_cull_mode,
+            )
+        };
+        let hint_render_pipeline =
+            overlay_render_pipeline("Hint Texture Shader", "fs_hint_main", false);
+        let poisoned_render_pipeline =
+            overlay_render_pipeline("Poisoned Texture Shader", "fs_poisoned_main", true);
+        let threat_render_pipeline =
+            overlay_render_pipeline("Threat Texture Shader", "fs_threat_main", true);
+        let threat_cell_render_pipeline =
+            overlay_render_pipeline("Threat Cell Texture Shader", "fs_threat_cell_main", false);
+
         // *Pipeline for Object not interacting with camera and light
         let custom_render_pipeline = {
             let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
@@ -819,8 +995,43 @@ impl State {
             usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
         });
 
-        let game_level = 1;
-        let game = game::Game::init(game_level);
+        let hint_arrow_instance_buffer =
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Hint Arrow Instance Buffer"),
+                contents: bytemuck::cast_slice(&[*arrow_instances_data
+                    .get(&GAME_PIECES_NAMES[0].0)
+                    .unwrap()]),
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            });
+        let hint_circle_instance_buffer =
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Hint Circle Instance Buffer"),
+                contents: bytemuck::cast_slice(&[*circle_instances_data.get(&(0, 0)).unwrap()]),
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            });
+        let threat_circle_instance_buffer =
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Threat Circle Instance Buffer"),
+                contents: bytemuck::cast_slice(
+                    &[*circle_instances_data.get(&(0, 0)).unwrap(); overlay::MAX_THREAT_CELLS],
+                ),
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            });
+
+        let game = Game::init(game_level);
+        let book = match OpeningBook::load(OPENING_BOOK_PATH) {
+            Ok(book) => Some(Arc::new(book)),
+            Err(e) => {
+                eprintln!("Playing without an opening book, {}: {}", OPENING_BOOK_PATH, e);
+                None
+            }
+        };
+        let keyboard = game::Keyboard::new();
+        let players = Players::new(
+            seats[0].player(game_level, &book, &keyboard)?,
+            seats[1].player(game_level, &book, &keyboard)?,
+        );
+        let selection = game::Selection::new(&game, &players);
 
         let custom_material = {
             let diffuse_bytes =
@@ -852,7 +1063,7 @@ impl State {
             )
         };
 
-        Self {
+        Ok(Self {
             instance,
             adapter,
             surface,
@@ -863,6 +1074,10 @@ impl State {
             clear_color,
             render_pipeline,
             arrow_circle_render_pipeline,
+            hint_render_pipeline,
+            poisoned_render_pipeline,
+            threat_render_pipeline,
+            threat_cell_render_pipeline,
             custom_render_pipeline,
             light_render_pipeline,
             // render_texture_pipeline,
@@ -896,11 +1111,23 @@ impl State {
             circle_model,
             circle_instances_data,
             circle_instance_buffer,
+            hint_arrow_instance_buffer,
+            hint_circle_instance_buffer,
+            hints: hint::Hints::new(),
+            threat_circle_instance_buffer,
+            overlay: overlay::Overlay::new(),
             game,
+            players,
+            seats,
+            keyboard,
+            book,
+            selection,
+            replay: None,
             game_level,
+            difficulty_menu: false,
             custom_material,
             mouse_pressed: false,
-        }
+        })
     }
 
     fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
@@ -938,25 +1165,66 @@ impl State {
                 virtual_keycode: Some(key),
                 ..
             }) => {
-                let game_keyboard_processed = if !self.game.ended {
-                    self.game.process_keyboard(*key, *state)
+                if self.replay.is_some() {
+                    return self.camera_specs.controller.process_keyboard(*key, *state)
+                        || self.process_replay_keyboard(*key, *state);
+                }
+
+                let menu_keyboard_processed = *state == ElementState::Pressed
+                    && match key {
+                        VirtualKeyCode::F5 => {
+                            self.save_game(Path::new(SAVE_FILE_PATH));
+                            true
+                        }
+                        VirtualKeyCode::F9 => {
+                            self.load_game(Path::new(SAVE_FILE_PATH));
+                            true
+                        }
+                        VirtualKeyCode::R => {
+                            self.start_replay();
+                            true
+                        }
+                        VirtualKeyCode::H => {
+                            self.toggle_hint();
+                            true
+                        }
+                        VirtualKeyCode::T => {
+                            self.toggle_overlay();
+                            true
+                        }
+                        VirtualKeyCode::L => {
+                            self.toggle_difficulty_menu();
+                            true
+                        }
+                        _ if self.difficulty_menu => match difficulty_key(*key) {
+                            Some(difficulty) => {
+                                self.set_level(difficulty.level());
+                                true
+                            }
+                            None => false,
+                        },
+                        _ => false,
+                    };
+
+                let game_keyboard_processed = if !self.game.is_over() {
+                    game::process_keyboard(
+                        &mut self.game,
+                        &mut self.players,
+                        &self.keyboard,
+                        &mut self.selection,
+                        *key,
+                        *state,
+                    )
                 } else {
                     false
                 };
 
                 if game_keyboard_processed {
-                    self.game.update(
-                        &self.queue,
-                        &self.arrow_instances_data,
-                        &self.arrow_instance_buffer,
-                        &self.circle_instances_data,
-                        &self.circle_instance_buffer,
-                        &self.game_pieces,
-                        &self.board_coords,
-                    );
+                    self.update_game_instances();
                 }
                 self.camera_specs.controller.process_keyboard(*key, *state)
                     || game_keyboard_processed
+                    || menu_keyboard_processed
             }
             DeviceEvent::MouseWheel { delta, .. } => {
                 self.camera_specs.controller.process_scroll(delta);
@@ -979,9 +1247,256 @@ impl State {
         }
     }
 
+    fn save_game(&self, path: &Path) {
+        match save::save_game(&self.game, path) {
+            Ok(()) => println!("Game saved to {}", path.display()),
+            Err(e) => eprintln!("Could not save the game to {}: {}", path.display(), e),
+        }
+    }
+
+    /// Starts a new game at `level` with new players for the seats, except for
+    /// remote ones, whose connection is kept.
+    fn reset_game(&mut self, level: usize) {
+        self.game_level = level;
+        self.game.reset(level);
+        self.hints.clear();
+        self.rebuild_players();
+    }
+
+    /// Plays the rest of the game at `level`.
+    fn set_level(&mut self, level: usize) {
+        self.game_level = level;
+        self.game.level = level;
+        self.difficulty_menu = false;
+        self.rebuild_players();
+        println!("Difficulty: {}", Difficulty::from_level(level));
+    }
+
+    // Players of the local seats, at the level of the game
+    fn rebuild_players(&mut self) {
+        let players = [&mut self.players.player, &mut self.players.opponent];
+        for (player, seat) in players.into_iter().zip(&self.seats) {
+            if seat.is_remote() {
+                continue;
+            }
+            match seat.player(self.game_level, &self.book, &self.keyboard) {
+                Ok(new_player) => *player = new_player,
+                Err(e) => eprintln!("Could not restart a player, keeping it as it was: {}", e),
+            }
+        }
+
+        self.selection = game::Selection::new(&self.game, &self.players);
+        self.update_game_instances();
+    }
+
+    fn toggle_difficulty_menu(&mut self) {
+        self.difficulty_menu = !self.difficulty_menu;
+        if self.difficulty_menu {
+            println!("{}", difficulty_menu(self.game_level));
+        }
+    }
+
+    fn load_game(&mut self, path: &Path) {
+        if !self.players.allows_undo() {
+            eprintln!("Games against a remote player cannot be loaded");
+            return;
+        }
+
+        match save::load_game(path) {
+            Ok(game) => {
+                println!("Game loaded from {}", path.display());
+                self.reset_game(game.level);
+                self.game = game;
+                self.selection = game::Selection::new(&self.game, &self.players);
+                self.update_game_instances();
+            }
+            Err(e) => eprintln!("Could not load the game from {}: {}", path.display(), e),
+        }
+    }
+
+    fn start_replay(&mut self) {
+        match replay::Replay::new(&self.game) {
+            Ok(replay) => {
+                println!("{}", replay.describe());
+                self.replay = Some(replay);
+            }
+            Err(e) => eprintln!("Could not replay the game: {}", e),
+        }
+        self.update_game_instances();
+    }
+
+    fn stop_replay(&mut self) {
+        self.replay = None;
+        self.update_game_instances();
+    }
+
+    fn process_replay_keyboard(&mut self, key: VirtualKeyCode, state: ElementState) -> bool {
+        let replay = match self.replay.as_mut() {
+            Some(replay) if state == ElementState::Pressed => replay,
+            _ => return false,
+        };
+
+        match key {
+            VirtualKeyCode::Right => {
+                replay.step_forward();
+            }
+            VirtualKeyCode::Left => {
+                replay.step_back();
+            }
+            VirtualKeyCode::Home => {
+                replay.rewind();
+            }
+            VirtualKeyCode::End => {
+                replay.fast_forward();
+            }
+            VirtualKeyCode::P => replay.toggle_auto_play(),
+            VirtualKeyCode::Up => replay.faster(),
+            VirtualKeyCode::Down => replay.slower(),
+            VirtualKeyCode::R => {
+                self.stop_replay();
+                return true;
+            }
+            _ => return false,
+        }
+
+        println!("{}", replay.describe());
+        self.update_game_instances();
+        true
+    }
+
+    /// Game on screen, which is the replayed one while a replay is running, and
+    /// the cursor to draw with it.
+    fn shown_game(&self) -> (&Game, game::Selection) {
+        match &self.replay {
+            Some(replay) => {
+                let (piece, coor) = replay.highlight();
+                (&replay.game, game::Selection { piece, coor })
+            }
+            None => (&self.game, self.selection),
+        }
+    }
+
+    fn title(&self) -> String {
+        match &self.replay {
+            Some(replay) => format!("Quarto - {}", replay.describe()),
+            None if self.difficulty_menu => {
+                format!("Quarto - {}", difficulty_menu(self.game_level))
+            }
+            None if self.players.get(self.game.turn()).is_thinking() => {
+                format!("Quarto - {:?} thinking...", self.game.turn())
+            }
+            None if self.hints.is_thinking() => "Quarto - Looking for a hint...".to_string(),
+            None => match self.shown_hint() {
+                Some(hint) => format!("Quarto - {}", hint::describe(hint)),
+                None if self.overlay.shown => format!("Quarto - {}", self.overlay.describe()),
+                None => "Quarto".to_string(),
+            },
+        }
+    }
+
+    fn update_game_instances(&mut self) {
+        let (shown_game, selection) = self.shown_game();
+        game::update_pieces(
+            shown_game,
+            &self.queue,
+            &self.game_pieces,
+            &self.game_piece_initial_instance_data,
+            &self.board_coords,
+        );
+        game::update_selection(
+            &selection,
+            &self.queue,
+            &self.arrow_instances_data,
+            &self.arrow_instance_buffer,
+            &self.circle_instances_data,
+            &self.circle_instance_buffer,
+        );
+
+        if let Some(hint) = self.shown_hint() {
+            hint::update(
+                hint,
+                &self.queue,
+                &self.arrow_instances_data,
+                &self.hint_arrow_instance_buffer,
+                &self.circle_instances_data,
+                &self.hint_circle_instance_buffer,
+            );
+        }
+
+        let shown_game = match &self.replay {
+            Some(replay) => &replay.game,
+            None => &self.game,
+        };
+        self.overlay.analyse(shown_game);
+        self.overlay.update(
+            &self.queue,
+            &self.circle_instances_data,
+            &self.threat_circle_instance_buffer,
+        );
+    }
+
+    /// Shows or hides the threats of the game on screen.
+    fn toggle_overlay(&mut self) {
+        self.overlay.toggle();
+        if self.overlay.shown {
+            println!("{}", self.overlay.describe());
+        }
+    }
+
+    /// Asks for a hint for the local player to move, or hides the one shown.
+    fn toggle_hint(&mut self) {
+        if self.shown_hint().is_some() {
+            self.hints.clear();
+            return;
+        }
+
+        let local_turn = self.players.get(self.game.turn()).is_local();
+        if self.replay.is_none() && !self.game.is_over() && local_turn {
+            println!("Looking for a hint...");
+            self.hints.request(self.game.position());
+        }
+    }
+
+    /// Hint for the position on screen, unless it is a replay.
+    fn shown_hint(&self) -> Option<&quarto_core::analysis::Hint> {
+        match self.replay {
+            Some(_) => None,
+            None => self.hints.shown(&self.game.position()),
+        }
+    }
+
     fn update(&mut self, dt: std::time::Duration) {
         self.camera_specs.update(&self.queue, dt);
         self.light_specs.update(&self.queue, dt);
+
+        let replay_stepped = match self.replay.as_mut() {
+            Some(replay) => replay.update(dt),
+            None => false,
+        };
+        if replay_stepped {
+            if let Some(replay) = &self.replay {
+                println!("{}", replay.describe());
+            }
+            self.update_game_instances();
+        }
+
+        if self.hints.poll() {
+            if let Some(hint) = self.shown_hint() {
+                println!("{}", hint::describe(hint));
+            }
+            self.update_game_instances();
+        }
+
+        if self.replay.is_none() {
+            match self.players.step(&mut self.game) {
+                Ok(Some(_)) => {
+                    self.selection.sync(&self.game, &self.players);
+                    self.update_game_instances();
+                }
+                Ok(None) => {}
+                Err(e) => eprintln!("{}", e),
+            }
+        }
     }
 
     fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
@@ -1089,8 +1604,13 @@ impl State {
             &self.light_specs.bind_group,
         );
 
-        //* Game Piece models
-        for (_, (game_piece, game_piece_instance_buffer, _)) in self.game_pieces.iter() {
+        //* Game Piece models, tinted when the threat overlay marks them
+        for (name, (game_piece, game_piece_instance_buffer, _)) in self.game_pieces.iter() {
+            render_pass.set_pipeline(match self.overlay.tint(name) {
+                Some(overlay::Tint::Poisoned) => &self.poisoned_render_pipeline,
+                Some(overlay::Tint::Threat) => &self.threat_render_pipeline,
+                None => &self.render_pipeline,
+            });
             render_pass.set_vertex_buffer(1, game_piece_instance_buffer.slice(..));
             render_pass.draw_model_instanced(
                 &game_piece,
@@ -1104,7 +1624,10 @@ impl State {
         //* Arrow model
         render_pass.set_pipeline(&self.arrow_circle_render_pipeline);
 
-        if !self.game.ended && self.game.available_pieces.len() > 0 {
+        let (shown_game, selection) = self.shown_game();
+        let show_cursor = self.replay.is_some() || !self.game.is_over();
+
+        if show_cursor && !shown_game.available_pieces.is_empty() {
             render_pass.set_vertex_buffer(1, self.arrow_instance_buffer.slice(..));
             render_pass.draw_model_instanced(
                 &self.arrow_model,
@@ -1114,7 +1637,7 @@ impl State {
             );
         }
         //* Circle model
-        if !self.game.ended && self.game.selected_coor.is_some() {
+        if show_cursor && selection.coor.is_some() {
             render_pass.set_vertex_buffer(1, self.circle_instance_buffer.slice(..));
             render_pass.draw_model_instanced(
                 &self.circle_model,
@@ -1123,6 +1646,41 @@ impl State {
                 &self.light_specs.bind_group,
             );
         }
+        //* Threat circles
+        let threat_cells = self.overlay.cell_count();
+        if threat_cells > 0 {
+            render_pass.set_pipeline(&self.threat_cell_render_pipeline);
+            render_pass.set_vertex_buffer(1, self.threat_circle_instance_buffer.slice(..));
+            render_pass.draw_model_instanced(
+                &self.circle_model,
+                0..threat_cells,
+                &self.camera_specs.bind_group,
+                &self.light_specs.bind_group,
+            );
+        }
+        //* Hint arrow and circle
+        if let Some(hint) = self.shown_hint() {
+            render_pass.set_pipeline(&self.hint_render_pipeline);
+
+            if hint.give.is_some() {
+                render_pass.set_vertex_buffer(1, self.hint_arrow_instance_buffer.slice(..));
+                render_pass.draw_model_instanced(
+                    &self.arrow_model,
+                    0..1,
+                    &self.camera_specs.bind_group,
+                    &self.light_specs.bind_group,
+                );
+            }
+            if hint.place.is_some() {
+                render_pass.set_vertex_buffer(1, self.hint_circle_instance_buffer.slice(..));
+                render_pass.draw_model_instanced(
+                    &self.circle_model,
+                    0..1,
+                    &self.camera_specs.bind_group,
+                    &self.light_specs.bind_group,
+                );
+            }
+        }
 
         drop(render_pass);
 
@@ -1136,6 +1694,48 @@ impl State {
 fn main() {
     //* Enabling logging
     env_logger::init();
+
+    let args = env::args().collect::<Vec<_>>();
+    let load_path = args
+        .iter()
+        .position(|arg| arg == "--load")
+        .and_then(|index| args.get(index + 1))
+        .map(std::path::PathBuf::from);
+    let replay_path = args
+        .iter()
+        .position(|arg| arg == "--replay")
+        .and_then(|index| args.get(index + 1))
+        .map(std::path::PathBuf::from);
+    let seat = |flag: &str, default: Seat| match args
+        .iter()
+        .position(|arg| arg == flag)
+        .and_then(|index| args.get(index + 1))
+    {
+        Some(text) => Seat::parse(text).unwrap_or_else(|| {
+            eprintln!(
+                "Unknown {} `{}`, expected keyboard, ai, mcts, host:<address>, join:<address> or engine:<command>",
+                flag, text
+            );
+            std::process::exit(1);
+        }),
+        None => default,
+    };
+    let seats = [seat("--player", Seat::Keyboard), seat("--opponent", Seat::Ai)];
+    let difficulty = match args
+        .iter()
+        .position(|arg| arg == "--level")
+        .and_then(|index| args.get(index + 1))
+    {
+        Some(text) => Difficulty::parse(text).unwrap_or_else(|| {
+            eprintln!(
+                "Unknown --level `{}`, expected a difficulty name or 1 to 6",
+                text
+            );
+            std::process::exit(1);
+        }),
+        None => Difficulty::Medium,
+    };
+
     let event_loop = EventLoop::new();
     let window = WindowBuilder::new()
         .with_title("Quarto")
@@ -1156,7 +1756,23 @@ fn main() {
         .build(&event_loop)
         .unwrap();
 
-    let mut state = pollster::block_on(State::new(&window));
+    let mut state = match pollster::block_on(State::new(&window, seats, difficulty.level())) {
+        Ok(state) => state,
+        Err(e) => {
+            eprintln!("Could not start the players: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(path) = load_path {
+        state.load_game(&path);
+    }
+    if let Some(path) = replay_path {
+        state.load_game(&path);
+        state.start_replay();
+    }
+
+    let mut title = state.title();
 
     let mut last_render_time = std::time::Instant::now();
 
@@ -1204,6 +1820,12 @@ fn main() {
                 last_render_time = now;
                 state.update(delta_time);
 
+                let new_title = state.title();
+                if new_title != title {
+                    window.set_title(&new_title);
+                    title = new_title;
+                }
+
                 match state.render() {
                     Ok(_) => {}
                     Err(wgpu::SurfaceError::Lost) => state.resize(state.size),
@@ -1211,10 +1833,39 @@ fn main() {
                     Err(e) => eprintln!("{:?}", e),
                 }
 
-                if state.game.ended {
+                if state.game.is_over() && state.replay.is_none() {
                     *control_flow = ControlFlow::Wait;
+
+                    match &state.game.outcome {
+                        GameOutcome::Won {
+                            by,
+                            line,
+                            coords,
+                            attributes,
+                        } => {
+                            let coords = coords
+                                .iter()
+                                .map(|coor| format!("({}, {})", coor.row, coor.col))
+                                .collect::<Vec<_>>();
+                            let attributes = attributes
+                                .iter()
+                                .map(|attribute| attribute.to_string())
+                                .collect::<Vec<_>>();
+
+                            println!(
+                                "{:?} won: {:?} {} shares {}",
+                                by,
+                                line,
+                                coords.join(" "),
+                                attributes.join(", ")
+                            );
+                        }
+                        GameOutcome::Draw => println!("Draw: the board is full"),
+                        GameOutcome::InProgress => {}
+                    }
+
                     let mut input = String::new();
-                    println!("Do you want to reset the game? (y/n): ");
+                    println!("Do you want to reset the game? (y/n, r to replay it): ");
                     std::io::stdin().read_line(&mut input).unwrap();
 
                     if input.chars().nth(0).is_some() {
@@ -1223,25 +1874,10 @@ fn main() {
                             *control_flow = ControlFlow::Poll;
                             println!("<<< Reseting The Game >>>");
 
-                            for (_, (_, game_piece_instance_buffer, _)) in state.game_pieces.iter()
-                            {
-                                state.queue.write_buffer(
-                                    &game_piece_instance_buffer,
-                                    0,
-                                    bytemuck::cast_slice(&[state.game_piece_initial_instance_data]),
-                                );
-                            }
-
-                            state.queue.write_buffer(
-                                &state.arrow_instance_buffer,
-                                0,
-                                bytemuck::cast_slice(&[*state
-                                    .arrow_instances_data
-                                    .get(&GAME_PIECES_NAMES[0].0)
-                                    .unwrap()]),
-                            );
-                            state.game_level += 1;
-                            state.game.reset(state.game_level);
+                            state.reset_game(state.game_level);
+                        } else if c == 'r' || c == 'R' {
+                            *control_flow = ControlFlow::Poll;
+                            state.start_replay();
                         } else if c == 'n' || c == 'N' {
                             *control_flow = ControlFlow::Exit;
                         }
@@ -0,0 +1,164 @@
+//! Threat overlay: the lines one piece away from a Quarto, and the available
+//! pieces that would complete one if handed over.
+
+use std::collections::HashMap;
+
+use quarto_core::{
+    analysis::{self, Threat},
+    notation, Coordinate, Game, PhaseKind, Piece,
+};
+
+use crate::instance;
+
+//* Up to one marked cell per board cell
+pub const MAX_THREAT_CELLS: usize = 16;
+//* Height of the threat circles above the cursor and hint ones
+const THREAT_LIFT: f32 = 0.02;
+
+/// How a piece model is drawn while the overlay is shown.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Tint {
+    /// Available piece that lets the other side win right away.
+    Poisoned,
+    /// Placed piece of a threatened line.
+    Threat,
+}
+
+/// Threats of the game on screen, worked out again on every change of the
+/// game and only drawn while the overlay is shown.
+#[derive(Default)]
+pub struct Overlay {
+    pub shown: bool,
+    threats: Vec<Threat>,
+    poisoned: Vec<Piece>,
+    /// Placed pieces of the threatened lines.
+    threatened: Vec<Piece>,
+    /// Free cells of the threatened lines.
+    cells: Vec<Coordinate>,
+}
+
+impl Overlay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn toggle(&mut self) {
+        self.shown = !self.shown;
+    }
+
+    /// Works out the threats of `game`. Pieces are only marked poisoned while
+    /// one of them is to be chosen for the other side.
+    pub fn analyse(&mut self, game: &Game) {
+        self.threats = analysis::threats(&game.board);
+        self.poisoned = match game.phase.kind() {
+            PhaseKind::ChoosePieceForOpponent if !game.is_over() => {
+                analysis::poisoned_pieces(&game.board, &game.available_pieces)
+            }
+            _ => vec![],
+        };
+
+        self.threatened.clear();
+        self.cells.clear();
+        for threat in &self.threats {
+            for piece in threat
+                .pieces
+                .iter()
+                .filter_map(|coor| game.board.get(*coor))
+            {
+                if !self.threatened.contains(&piece) {
+                    self.threatened.push(piece);
+                }
+            }
+            if !self.cells.contains(&threat.cell) {
+                self.cells.push(threat.cell);
+            }
+        }
+    }
+
+    /// Tint of the piece model `name`, if it is marked.
+    pub fn tint(&self, name: &str) -> Option<Tint> {
+        if !self.shown {
+            return None;
+        }
+
+        if self.poisoned.iter().any(|piece| piece.name() == name) {
+            Some(Tint::Poisoned)
+        } else if self.threatened.iter().any(|piece| piece.name() == name) {
+            Some(Tint::Threat)
+        } else {
+            None
+        }
+    }
+
+    /// Number of threat cells to draw from the instance buffer.
+    pub fn cell_count(&self) -> u32 {
+        if self.shown {
+            self.cells.len() as u32
+        } else {
+            0
+        }
+    }
+
+    /// One line readout of the threats, e.g. `Threats: d1 Light, Round`.
+    pub fn describe(&self) -> String {
+        if self.threats.is_empty() {
+            return "No threats".to_string();
+        }
+
+        let threats = self
+            .threats
+            .iter()
+            .map(|threat| {
+                let attributes = threat
+                    .attributes
+                    .iter()
+                    .map(|attribute| attribute.to_string())
+                    .collect::<Vec<_>>();
+                format!(
+                    "{} {}",
+                    notation::format_coordinate(threat.cell),
+                    attributes.join(", ")
+                )
+            })
+            .collect::<Vec<_>>();
+        let mut text = format!("Threats: {}", threats.join("; "));
+
+        if !self.poisoned.is_empty() {
+            let poisoned = self
+                .poisoned
+                .iter()
+                .map(|piece| notation::format_piece(*piece))
+                .collect::<Vec<_>>();
+            text += &format!(", poisoned: {}", poisoned.join(" "));
+        }
+
+        text
+    }
+
+    /// Writes the instance buffer of the threat circles, one instance per
+    /// threatened cell.
+    pub fn update(
+        &self,
+        queue: &wgpu::Queue,
+        circle_instances_data: &HashMap<(i8, i8), instance::InstanceRaw>,
+        threat_circle_instance_buffer: &wgpu::Buffer,
+    ) {
+        let instances_data = self
+            .cells
+            .iter()
+            .map(|coor| {
+                let mut instance_data = *circle_instances_data.get(&(coor.row, coor.col)).unwrap();
+                instance_data.model[3][1] += THREAT_LIFT;
+                instance_data
+            })
+            .collect::<Vec<_>>();
+
+        if !instances_data.is_empty() {
+            queue.write_buffer(
+                threat_circle_instance_buffer,
+                0,
+                bytemuck::cast_slice(&instances_data),
+            );
+        }
+    }
+}
@@ -121,13 +121,16 @@ fn fs_arrow_main(in: TextureVertexOutput) -> [[location(0)]] vec4<f32> {
     return object_color;
 }
 
+// *Hint arrow and circle: the cursor texture tinted green
 [[stage(fragment)]]
-fn fs_main(in: TextureVertexOutput) -> [[location(0)]] vec4<f32> {
-    // return textureSample(t_diffuse, s_diffuse, in.tex_coords);
-
-      var object_color : vec4<f32> = textureSample(t_diffuse, s_diffuse, in.tex_coords);
-
+fn fs_hint_main(in: TextureVertexOutput) -> [[location(0)]] vec4<f32> {
+    var object_color : vec4<f32> = textureSample(t_diffuse, s_diffuse, in.tex_coords);
+    let hint_color = vec3<f32>(0.2, 0.9, 0.3);
+    return vec4<f32>(mix(object_color.xyz, hint_color, 0.6), object_color.a);
+}
 
+// *Ambient, diffuse and specular lighting of object_color
+fn lit(in: TextureVertexOutput, object_color: vec4<f32>) -> vec4<f32> {
     // let object_normal: vec4<f32> = textureSample(t_normal, s_normal, in.tex_coords);
 
     // Create the lighting vectors
@@ -162,6 +165,40 @@ fn fs_main(in: TextureVertexOutput) -> [[location(0)]] vec4<f32> {
     return vec4<f32>(result, object_color.a);
 }
 
+// *Threat circles: the cursor texture tinted orange
+[[stage(fragment)]]
+fn fs_threat_cell_main(in: TextureVertexOutput) -> [[location(0)]] vec4<f32> {
+    var object_color : vec4<f32> = textureSample(t_diffuse, s_diffuse, in.tex_coords);
+    let threat_color = vec3<f32>(1.0, 0.55, 0.1);
+    return vec4<f32>(mix(object_color.xyz, threat_color, 0.6), object_color.a);
+}
+
+[[stage(fragment)]]
+fn fs_main(in: TextureVertexOutput) -> [[location(0)]] vec4<f32> {
+    // return textureSample(t_diffuse, s_diffuse, in.tex_coords);
+
+      var object_color : vec4<f32> = textureSample(t_diffuse, s_diffuse, in.tex_coords);
+
+    return lit(in, object_color);
+}
+
+// *Poisoned pieces: lit like the others, tinted red
+[[stage(fragment)]]
+fn fs_poisoned_main(in: TextureVertexOutput) -> [[location(0)]] vec4<f32> {
+    var object_color : vec4<f32> = textureSample(t_diffuse, s_diffuse, in.tex_coords);
+    let poisoned_color = vec3<f32>(0.9, 0.1, 0.1);
+    return lit(in, vec4<f32>(mix(object_color.xyz, poisoned_color, 0.5), object_color.a));
+}
+
+// *Pieces of threatened lines: lit like the others, tinted orange
+[[stage(fragment)]]
+fn fs_threat_main(in: TextureVertexOutput) -> [[location(0)]] vec4<f32> {
+    var object_color : vec4<f32> = textureSample(t_diffuse, s_diffuse, in.tex_coords);
+    let threat_color = vec3<f32>(1.0, 0.55, 0.1);
+    return lit(in, vec4<f32>(mix(object_color.xyz, threat_color, 0.5), object_color.a));
+}
+
+
 
 
 // TODO: Check out if condition statement in wgsl